@@ -0,0 +1,48 @@
+//! Taking timesheet descriptions from Jira issues
+
+use crate::KimaiError;
+use serde::Deserialize;
+
+/// The `[jira]` section of the config file
+#[derive(Debug, Clone, Deserialize)]
+pub struct JiraConfig {
+    host: String,
+    user: String,
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Issue {
+    fields: IssueFields,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueFields {
+    summary: String,
+}
+
+/// Get the summary of the given Jira issue, like `PROJ-123`.
+pub async fn get_issue_summary(config: &JiraConfig, ticket: &str) -> Result<String, KimaiError> {
+    let invalid = || KimaiError::Config(format!("Invalid Jira host \"{}\"!", config.host));
+    let mut url = reqwest::Url::parse(&format!(
+        "{}/rest/api/2/issue",
+        config.host.trim_end_matches('/')
+    ))
+    .map_err(|_| invalid())?;
+    // Percent-encode the ticket, so it can't reach other paths of the API
+    url.path_segments_mut().map_err(|_| invalid())?.push(ticket);
+    let response = reqwest::Client::new()
+        .get(url)
+        .query(&[("fields", "summary")])
+        .basic_auth(&config.user, Some(&config.token))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(KimaiError::Api(format!(
+            "Jira issue {}: {}",
+            ticket,
+            response.text().await?
+        )));
+    }
+    Ok(response.json::<Issue>().await?.fields.summary)
+}
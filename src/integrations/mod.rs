@@ -0,0 +1,3 @@
+//! Clients for services other than Kimai that data can be taken from
+
+pub mod jira;
@@ -1,16 +1,41 @@
+mod integrations;
+mod output;
+mod template;
+
 use chrono::prelude::*;
 use clap::crate_name;
-use prettytable::{cell, format, row, Table};
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use integrations::jira::{self, JiraConfig};
+use output::{clockify, harvest, ical, markdown, org, toggl};
+use prettytable::{format, row, Cell, Row, Table};
 use reqwest::header::{self, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::fs;
-use std::path::Path;
-use std::process::Command;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use template::Template;
 
 pub const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M";
 pub const TIME_FORMAT: &str = "%H:%M";
+/// Format of ISO 8601 datetimes with an offset, as printed by `git log --format=%ai`
+pub const OFFSET_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Check that a strftime format string can be used to format datetimes.
+pub fn validate_datetime_format(format: &str) -> Result<(), KimaiError> {
+    use std::fmt::Write;
+    let mut formatted = String::new();
+    write!(
+        formatted,
+        "{}",
+        Local.ymd(2021, 6, 1).and_hms(9, 0, 0).format(format)
+    )
+    .map_err(|_| KimaiError::Other(format!("invalid datetime format \"{}\"", format)))
+}
 
 trait QueryValue {
     fn process(&self) -> String;
@@ -25,6 +50,12 @@ impl QueryValue for Vec<usize> {
     }
 }
 
+impl QueryValue for Vec<String> {
+    fn process(&self) -> String {
+        self.join(",")
+    }
+}
+
 impl QueryValue for &str {
     fn process(&self) -> String {
         self.to_string()
@@ -43,18 +74,51 @@ impl QueryValue for usize {
     }
 }
 
+impl QueryValue for bool {
+    fn process(&self) -> String {
+        match self {
+            true => "1".to_string(),
+            false => "0".to_string(),
+        }
+    }
+}
+
+impl QueryValue for TimesheetUser {
+    fn process(&self) -> String {
+        match self {
+            TimesheetUser::Id(id) => id.to_string(),
+            TimesheetUser::All => "all".to_string(),
+        }
+    }
+}
+
 impl QueryValue for DateTime<Local> {
     fn process(&self) -> String {
-        self.naive_local().to_string()
+        self.format("%Y-%m-%dT%H:%M:%S").to_string()
     }
 }
 
+/// Build the query parameters for a request.
+///
+/// Parameters given as `(key, value)` are sent once with their value
+/// processed by `QueryValue`, while parameters given as `[key, values]` are
+/// sent once per value, e.g. `customers[]=1&customers[]=2`.
 macro_rules! query{
-    ($(($key:expr, $value:expr)),*) => {{
-        let mut queries = HashMap::new();
-        $(if let Some(v) = $value {
-            queries.insert($key, QueryValue::process(&v));
-        };)*
+    (@push $queries:ident, ($key:expr, $value:expr)) => {
+        if let Some(v) = $value {
+            $queries.push(($key, QueryValue::process(&v)));
+        }
+    };
+    (@push $queries:ident, [$key:expr, $values:expr]) => {
+        if let Some(v) = $values {
+            for i in v {
+                $queries.push(($key, QueryValue::process(&i)));
+            }
+        }
+    };
+    ($($item:tt),*) => {{
+        let mut queries: Vec<(&str, String)> = Vec::new();
+        $(query!(@push queries, $item);)*
         if queries.is_empty() {
             None
         } else {
@@ -63,14 +127,54 @@ macro_rules! query{
     }}
 }
 
+/// Whose timesheet records to get, where Kimai falls back to the current
+/// user's records without the permission to view other timesheets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimesheetUser {
+    Id(usize),
+    All,
+}
+
+/// Filters and paging of a request for timesheet records, where `None`
+/// leaves a filter out
+#[derive(Debug, Clone, Default)]
+pub struct TimesheetQuery {
+    pub user: Option<TimesheetUser>,
+    /// The `customers`, `projects` and `activities` filters are sent as
+    /// arrays (`customers[]=1&customers[]=2`), which is the format accepted by
+    /// `GET /api/timesheets` since Kimai 1.0. The deprecated single value
+    /// parameters (`customer`, `project` and `activity`) are not used.
+    pub customers: Option<Vec<usize>>,
+    pub projects: Option<Vec<usize>>,
+    pub activities: Option<Vec<usize>>,
+    /// `begin` and `end` limit the range the records begin in, as local time
+    /// without offset (`2021-06-01T09:00:00`). They are unrelated to the
+    /// begin and end of the records themselves, so a record beginning before
+    /// `end` is listed even if it ends after it.
+    pub begin: Option<DateTime<Local>>,
+    pub end: Option<DateTime<Local>>,
+    pub exported: Option<bool>,
+    pub billable: Option<bool>,
+    pub tags: Option<Vec<String>>,
+    /// Kimai's free search term, matching the description and other fields
+    /// of the records
+    pub term: Option<String>,
+    pub page: Option<usize>,
+    pub size: Option<usize>,
+}
+
 #[derive(Debug)]
 pub enum KimaiError {
     XdgBaseDirectories(String),
     IO(String),
     Toml(String),
+    Csv(String),
+    Yaml(String),
+    Json(String),
     Utf8(String),
     Reqwest(String),
     ChronoParse(String),
+    DurationParse(String),
     Config(String),
     Api(String),
     Other(String),
@@ -84,9 +188,13 @@ impl fmt::Display for KimaiError {
             KimaiError::XdgBaseDirectories(e) => write!(f, "XDG BaseDirectories Error: {}", e),
             KimaiError::IO(e) => write!(f, "IO Error: {}", e),
             KimaiError::Toml(e) => write!(f, "TOML Error: {}", e),
+            KimaiError::Csv(e) => write!(f, "CSV Error: {}", e),
+            KimaiError::Yaml(e) => write!(f, "YAML Error: {}", e),
+            KimaiError::Json(e) => write!(f, "JSON Error: {}", e),
             KimaiError::Utf8(e) => write!(f, "UTF-8 Error: {}", e),
             KimaiError::Reqwest(e) => write!(f, "Reqwest Error: {}", e),
             KimaiError::ChronoParse(e) => write!(f, "Chrono Parser Error: {}", e),
+            KimaiError::DurationParse(e) => write!(f, "Duration Parser Error: {}", e),
             KimaiError::Config(e) => write!(f, "Config Error: {}", e),
             KimaiError::Api(e) => write!(f, "API Error: {}", e),
             KimaiError::Other(e) => write!(f, "Error: {}", e),
@@ -112,6 +220,24 @@ impl From<toml::de::Error> for KimaiError {
     }
 }
 
+impl From<toml::ser::Error> for KimaiError {
+    fn from(error: toml::ser::Error) -> KimaiError {
+        KimaiError::Toml(error.to_string())
+    }
+}
+
+impl From<csv::Error> for KimaiError {
+    fn from(error: csv::Error) -> KimaiError {
+        KimaiError::Csv(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for KimaiError {
+    fn from(error: serde_json::Error) -> KimaiError {
+        KimaiError::Json(error.to_string())
+    }
+}
+
 impl From<std::str::Utf8Error> for KimaiError {
     fn from(error: std::str::Utf8Error) -> KimaiError {
         KimaiError::Utf8(error.to_string())
@@ -135,52 +261,514 @@ struct ConfigFile {
     host: String,
     token: Option<String>,
     pass_path: Option<String>,
+    #[serde(default)]
+    default_hidden_columns: Vec<String>,
+    /// Columns of the timesheet table in their order
+    columns: Option<Vec<String>>,
+    start_of_day: Option<String>,
+    /// Time gaps before the first record of a day are counted from
+    work_start_time: Option<String>,
+    billing_increment_minutes: Option<u64>,
+    datetime_format: Option<String>,
+    idle_threshold: Option<String>,
+    description_max_length: Option<usize>,
+    jira: Option<JiraConfig>,
+    working_hours_per_day: Option<f64>,
+    working_days_per_week: Option<u32>,
+    max_active_timers: Option<usize>,
+    budget_warning_threshold: Option<f64>,
+    budget_critical_threshold: Option<f64>,
+    resolve_concurrency: Option<usize>,
+    /// Dates not worked on, as `YYYY-MM-DD`
+    #[serde(default)]
+    holidays: Vec<String>,
+    /// Canonical tags, by alias
+    #[serde(default)]
+    tag_aliases: BTreeMap<String, String>,
+    /// Further Kimai instances, by profile name
+    #[serde(default)]
+    profiles: BTreeMap<String, ConfigFile>,
+    /// Profile used if none is selected
+    default_profile: Option<String>,
+}
+
+impl ConfigFile {
+    fn from_path(path: &Path) -> Result<Self, KimaiError> {
+        let config_string = fs::read_to_string(path)?;
+        Ok(toml::from_str::<ConfigFile>(&config_string)?)
+    }
+
+    /// Get the name of the given profile, falling back to the
+    /// `default_profile` and then to the top-level fields.
+    fn selected_profile(&self, profile: Option<&str>) -> String {
+        match profile {
+            Some(p) => p.to_string(),
+            None => self
+                .default_profile
+                .clone()
+                .unwrap_or_else(|| DEFAULT_PROFILE.to_string()),
+        }
+    }
+
+    /// Profiles can only be defined at the top level of the config file.
+    fn check_not_nested(&self, name: &str) -> Result<(), KimaiError> {
+        match self.profiles.is_empty() {
+            true => Ok(()),
+            false => Err(KimaiError::Config(format!(
+                "profile {}: profiles can't have profiles of their own",
+                name
+            ))),
+        }
+    }
+
+    fn resolve_token(&self) -> Result<String, KimaiError> {
+        if let Some(t) = &self.token {
+            Ok(t.to_string())
+        } else if let Some(p) = &self.pass_path {
+            let pass_cmd = Command::new("pass").arg(p).output()?;
+            Ok(std::str::from_utf8(&pass_cmd.stdout)?.trim().into())
+        } else {
+            Err(KimaiError::Config("No token give in config!".to_string()))
+        }
+    }
+}
+
+/// Environment variable overriding the host of the config file
+pub const HOST_ENV_VAR: &str = "KIMAI_HOST";
+
+/// Environment variable overriding the token of the config file
+pub const TOKEN_ENV_VAR: &str = "KIMAI_TOKEN";
+
+/// Config values given by environment variables
+#[derive(Debug, Default)]
+struct EnvOverrides {
+    host: Option<String>,
+    token: Option<String>,
 }
 
+impl EnvOverrides {
+    fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Take the overrides from the variables looked up by the given function.
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        EnvOverrides {
+            host: var(HOST_ENV_VAR),
+            token: var(TOKEN_ENV_VAR),
+        }
+    }
+
+    /// Replace the values of the config file by the given ones. A given
+    /// token makes running `pass` unnecessary.
+    fn apply(&self, config_file: &mut ConfigFile) {
+        if let Some(host) = &self.host {
+            config_file.host = host.clone();
+        }
+        if let Some(token) = &self.token {
+            config_file.token = Some(token.clone());
+            config_file.pass_path = None;
+        }
+    }
+}
+
+/// Hours after which a running record is considered possibly forgotten
+const DEFAULT_IDLE_THRESHOLD_HOURS: i64 = 2;
+
+/// Characters after which descriptions are cut off in the timesheet table
+const DEFAULT_DESCRIPTION_MAX_LENGTH: usize = 50;
+
+/// Number of records which may be active at the same time
+const DEFAULT_MAX_ACTIVE_TIMERS: usize = 1;
+
+/// Number of requests to resolve single IDs which are sent at the same time
+const DEFAULT_RESOLVE_CONCURRENCY: usize = 5;
+
 #[derive(Debug)]
 pub struct Config {
+    /// Name of the profile this config was read from
+    profile: String,
     host: String,
     token: String,
+    default_hidden_columns: Vec<Column>,
+    columns: Option<Vec<Column>>,
+    start_of_day: NaiveTime,
+    work_start_time: Option<NaiveTime>,
+    billing_increment_minutes: Option<u64>,
+    datetime_format: Option<String>,
+    idle_threshold: chrono::Duration,
+    description_max_length: usize,
+    jira: Option<JiraConfig>,
+    working_time_model: WorkingTimeModel,
+    max_active_timers: usize,
+    budget_thresholds: BudgetThresholds,
+    resolve_concurrency: usize,
+    tag_aliases: BTreeMap<String, String>,
 }
 
 impl Config {
     pub fn new(host: String, token: String) -> Self {
         Config {
+            profile: DEFAULT_PROFILE.to_string(),
             host,
             token,
+            default_hidden_columns: Vec::new(),
+            columns: None,
+            start_of_day: NaiveTime::from_hms(9, 0, 0),
+            work_start_time: None,
+            billing_increment_minutes: None,
+            datetime_format: None,
+            idle_threshold: chrono::Duration::hours(DEFAULT_IDLE_THRESHOLD_HOURS),
+            description_max_length: DEFAULT_DESCRIPTION_MAX_LENGTH,
+            jira: None,
+            working_time_model: WorkingTimeModel::default(),
+            max_active_timers: DEFAULT_MAX_ACTIVE_TIMERS,
+            budget_thresholds: BudgetThresholds::default(),
+            resolve_concurrency: DEFAULT_RESOLVE_CONCURRENCY,
+            tag_aliases: BTreeMap::new(),
         }
     }
-    pub fn from_path(path: &Path) -> Result<Self, KimaiError> {
-        let config_string = fs::read_to_string(path)?;
-        let config_file = toml::from_str::<ConfigFile>(&config_string)?;
-        if let Some(t) = config_file.token {
-            Ok(Config {
-                host: config_file.host,
-                token: t,
-            })
-        } else if let Some(p) = config_file.pass_path {
-            let pass_cmd = Command::new("pass").arg(p).output()?;
-            Ok(Config {
-                host: config_file.host,
-                token: std::str::from_utf8(&pass_cmd.stdout)?.trim().into(),
-            })
-        } else {
-            Err(KimaiError::Config(
-                "No token give in config!".to_string(),
-            ))
+    /// Read the config of the given profile, falling back to the
+    /// `default_profile` of the config file and then to the top-level fields.
+    /// Values given by environment variables take precedence over the file.
+    pub fn from_path(path: &Path, profile: Option<&str>) -> Result<Self, KimaiError> {
+        Self::from_config_file_with_profile(
+            ConfigFile::from_path(path)?,
+            profile,
+            &EnvOverrides::from_env(),
+        )
+    }
+
+    fn from_config_file_with_profile(
+        mut config_file: ConfigFile,
+        profile: Option<&str>,
+        env: &EnvOverrides,
+    ) -> Result<Self, KimaiError> {
+        let name = config_file.selected_profile(profile);
+        if name == DEFAULT_PROFILE {
+            env.apply(&mut config_file);
+            return Self::from_config_file(config_file);
+        }
+        let mut profile = config_file
+            .profiles
+            .remove(&name)
+            .ok_or_else(|| KimaiError::Config(format!("profile {} not found", name)))?;
+        profile.check_not_nested(&name)?;
+        env.apply(&mut profile);
+        let mut config = Self::from_config_file(profile)
+            .map_err(|e| KimaiError::Config(format!("profile {}: {}", name, e)))?;
+        config.profile = name;
+        Ok(config)
+    }
+
+    /// Read the top-level config, named `DEFAULT_PROFILE`, and the config of
+    /// every profile. Values given by environment variables take precedence
+    /// over the file for the selected profile, as they do when reading only
+    /// that one.
+    pub fn profiles_from_path(
+        path: &Path,
+        profile: Option<&str>,
+    ) -> Result<Vec<(String, Self)>, KimaiError> {
+        Self::profiles_from_config_file(
+            ConfigFile::from_path(path)?,
+            profile,
+            &EnvOverrides::from_env(),
+        )
+    }
+
+    /// Without a config file, `HOST_ENV_VAR` and `TOKEN_ENV_VAR` make up the
+    /// only profile.
+    pub fn profiles_from_xdg(profile: Option<&str>) -> Result<Vec<(String, Self)>, KimaiError> {
+        Self::profiles_from_found_path(xdg_config_path(), profile, &EnvOverrides::from_env())
+    }
+
+    fn profiles_from_found_path(
+        path: Result<PathBuf, KimaiError>,
+        profile: Option<&str>,
+        env: &EnvOverrides,
+    ) -> Result<Vec<(String, Self)>, KimaiError> {
+        match path {
+            Ok(path) => {
+                Self::profiles_from_config_file(ConfigFile::from_path(&path)?, profile, env)
+            }
+            Err(e) => Self::from_found_path(Err(e), profile, env)
+                .map(|config| vec![(config.profile.clone(), config)]),
+        }
+    }
+
+    fn profiles_from_config_file(
+        mut config_file: ConfigFile,
+        profile: Option<&str>,
+        env: &EnvOverrides,
+    ) -> Result<Vec<(String, Self)>, KimaiError> {
+        let selected = config_file.selected_profile(profile);
+        if selected != DEFAULT_PROFILE && !config_file.profiles.contains_key(&selected) {
+            return Err(KimaiError::Config(format!(
+                "profile {} not found",
+                selected
+            )));
         }
+        let profiles = std::mem::take(&mut config_file.profiles);
+        if selected == DEFAULT_PROFILE {
+            env.apply(&mut config_file);
+        }
+        let mut configs = vec![(
+            DEFAULT_PROFILE.to_string(),
+            Self::from_config_file(config_file)?,
+        )];
+        for (name, mut profile) in profiles {
+            profile.check_not_nested(&name)?;
+            if name == selected {
+                env.apply(&mut profile);
+            }
+            let mut config = Self::from_config_file(profile)
+                .map_err(|e| KimaiError::Config(format!("profile {}: {}", name, e)))?;
+            config.profile = name.clone();
+            configs.push((name, config));
+        }
+        Ok(configs)
+    }
+
+    fn from_config_file(config_file: ConfigFile) -> Result<Self, KimaiError> {
+        Ok(Config {
+            profile: DEFAULT_PROFILE.to_string(),
+            token: config_file.resolve_token()?,
+            default_hidden_columns: config_file
+                .default_hidden_columns
+                .iter()
+                .map(|c| c.parse())
+                .collect::<Result<Vec<Column>, KimaiError>>()
+                .map_err(|e| KimaiError::Config(e.to_string()))?,
+            columns: match &config_file.columns {
+                Some(columns) => Some(
+                    columns
+                        .iter()
+                        .map(|c| c.parse())
+                        .collect::<Result<Vec<Column>, KimaiError>>()
+                        .map_err(|e| KimaiError::Config(format!("columns: {}", e)))?,
+                ),
+                None => None,
+            },
+            start_of_day: match &config_file.start_of_day {
+                Some(t) => NaiveTime::parse_from_str(t, "%H:%M")
+                    .map_err(|e| KimaiError::Config(format!("start_of_day: {}", e)))?,
+                None => NaiveTime::from_hms(9, 0, 0),
+            },
+            work_start_time: match &config_file.work_start_time {
+                Some(t) => Some(
+                    NaiveTime::parse_from_str(t, "%H:%M")
+                        .map_err(|e| KimaiError::Config(format!("work_start_time: {}", e)))?,
+                ),
+                None => None,
+            },
+            billing_increment_minutes: config_file.billing_increment_minutes,
+            datetime_format: match config_file.datetime_format {
+                Some(f) => Some(
+                    validate_datetime_format(&f)
+                        .map(|_| f)
+                        .map_err(|e| KimaiError::Config(format!("datetime_format: {}", e)))?,
+                ),
+                None => None,
+            },
+            idle_threshold: match &config_file.idle_threshold {
+                Some(t) => parse_relative_duration(t)
+                    .map_err(|e| KimaiError::Config(format!("idle_threshold: {}", e)))?,
+                None => chrono::Duration::hours(DEFAULT_IDLE_THRESHOLD_HOURS),
+            },
+            description_max_length: config_file
+                .description_max_length
+                .unwrap_or(DEFAULT_DESCRIPTION_MAX_LENGTH),
+            jira: config_file.jira,
+            working_time_model: WorkingTimeModel {
+                hours_per_day: match config_file.working_hours_per_day {
+                    Some(h) if !(0.0..=24.0).contains(&h) => {
+                        return Err(KimaiError::Config(
+                            "working_hours_per_day: must be between 0 and 24".to_string(),
+                        ))
+                    }
+                    Some(h) => h,
+                    None => WorkingTimeModel::default().hours_per_day,
+                },
+                days_per_week: match config_file.working_days_per_week {
+                    Some(d) if d > 7 => {
+                        return Err(KimaiError::Config(
+                            "working_days_per_week: must be at most 7".to_string(),
+                        ))
+                    }
+                    Some(d) => d,
+                    None => WorkingTimeModel::default().days_per_week,
+                },
+                holidays: config_file
+                    .holidays
+                    .iter()
+                    .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d"))
+                    .collect::<Result<Vec<NaiveDate>, _>>()
+                    .map_err(|e| KimaiError::Config(format!("holidays: {}", e)))?,
+            },
+            max_active_timers: config_file
+                .max_active_timers
+                .unwrap_or(DEFAULT_MAX_ACTIVE_TIMERS),
+            budget_thresholds: BudgetThresholds::new(
+                config_file
+                    .budget_warning_threshold
+                    .unwrap_or_else(|| BudgetThresholds::default().warning),
+                config_file
+                    .budget_critical_threshold
+                    .unwrap_or_else(|| BudgetThresholds::default().critical),
+            )
+            .map_err(|e| KimaiError::Config(format!("budget thresholds: {}", e)))?,
+            resolve_concurrency: match config_file.resolve_concurrency {
+                Some(0) => {
+                    return Err(KimaiError::Config(
+                        "resolve_concurrency: must be at least 1".to_string(),
+                    ))
+                }
+                Some(c) => c,
+                None => DEFAULT_RESOLVE_CONCURRENCY,
+            },
+            tag_aliases: config_file.tag_aliases,
+            host: config_file.host,
+        })
     }
 
-    pub fn from_xdg() -> Result<Self, KimaiError> {
-        let xdg_dirs = xdg::BaseDirectories::with_prefix(crate_name!())?;
-        let config_path = xdg_dirs
-            .find_config_file("config.toml")
-            .ok_or_else(|| KimaiError::Config("config file not found!".to_string()))?;
-        Self::from_path(Path::new(&config_path))
+    /// Without a config file, `HOST_ENV_VAR` and `TOKEN_ENV_VAR` suffice.
+    pub fn from_xdg(profile: Option<&str>) -> Result<Self, KimaiError> {
+        Self::from_found_path(xdg_config_path(), profile, &EnvOverrides::from_env())
+    }
+
+    /// Load the config file if found, or create a config from the overrides
+    /// alone if they give both host and token. Without a config file, there
+    /// is no profile besides the default one.
+    fn from_found_path(
+        path: Result<PathBuf, KimaiError>,
+        profile: Option<&str>,
+        env: &EnvOverrides,
+    ) -> Result<Self, KimaiError> {
+        match path {
+            Ok(path) => {
+                Self::from_config_file_with_profile(ConfigFile::from_path(&path)?, profile, env)
+            }
+            Err(_) if profile.is_some_and(|p| p != DEFAULT_PROFILE) => Err(KimaiError::Config(
+                format!("profile {} not found", profile.unwrap_or_default()),
+            )),
+            Err(e) => match env {
+                EnvOverrides {
+                    host: Some(host),
+                    token: Some(token),
+                } => Ok(Config::new(host.clone(), token.clone())),
+                _ => Err(e),
+            },
+        }
     }
 }
 
-#[derive(Debug, Deserialize)]
+fn xdg_config_path() -> Result<PathBuf, KimaiError> {
+    let xdg_dirs = xdg::BaseDirectories::with_prefix(crate_name!())?;
+    xdg_dirs
+        .find_config_file("config.toml")
+        .ok_or_else(|| KimaiError::Config("config file not found!".to_string()))
+}
+
+/// Directory within the XDG data directory holding the local state
+const STATE_PREFIX: &str = "kimai-rs";
+
+/// Read a JSON file of local state kept in the XDG data directory, which is
+/// empty if the file doesn't exist yet.
+fn read_state<T>(name: &str) -> Result<T, KimaiError>
+where
+    T: for<'de> Deserialize<'de> + Default,
+{
+    let xdg_dirs = xdg::BaseDirectories::with_prefix(STATE_PREFIX)?;
+    match xdg_dirs.find_data_file(name) {
+        Some(path) => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+        None => Ok(T::default()),
+    }
+}
+
+/// Write a JSON file of local state to the XDG data directory.
+fn write_state<T: Serialize>(name: &str, state: &T) -> Result<(), KimaiError> {
+    let xdg_dirs = xdg::BaseDirectories::with_prefix(STATE_PREFIX)?;
+    fs::write(
+        xdg_dirs.place_data_file(name)?,
+        serde_json::to_string_pretty(state)?,
+    )?;
+    Ok(())
+}
+
+const ESTIMATES_FILE: &str = "estimates.json";
+
+/// Estimate of how long a timesheet record is going to take. Kimai has no
+/// notion of those, so they are kept locally.
+#[derive(Debug, Deserialize, Serialize)]
+struct Estimate {
+    record_id: usize,
+    estimate_seconds: i64,
+}
+
+const NOTES_FILE: &str = "notes.json";
+
+/// Personal note on a timesheet record. Unlike the description it is only
+/// kept locally and never sent to Kimai.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+struct PersonalNote {
+    note: String,
+}
+
+/// Set the personal note of a timesheet record, removing it if the note is
+/// empty.
+fn set_personal_note(record_id: usize, note: &str) -> Result<(), KimaiError> {
+    let mut notes: BTreeMap<usize, PersonalNote> = read_state(NOTES_FILE)?;
+    match note.trim() {
+        "" => notes.remove(&record_id),
+        n => notes.insert(
+            record_id,
+            PersonalNote {
+                note: n.to_string(),
+            },
+        ),
+    };
+    write_state(NOTES_FILE, &notes)
+}
+
+const RECENT_PROJECTS_FILE: &str = "recent-projects.json";
+
+/// Number of recently used projects that are remembered
+const MAX_RECENT_PROJECTS: usize = 10;
+
+/// A project and activity a record was recently begun with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+struct RecentProject {
+    project: usize,
+    activity: usize,
+}
+
+/// Put a project and activity first in the recently used ones, dropping the
+/// oldest beyond `MAX_RECENT_PROJECTS`.
+fn remember_recent_project(recent: &mut Vec<RecentProject>, used: RecentProject) {
+    recent.retain(|r| *r != used);
+    recent.insert(0, used);
+    recent.truncate(MAX_RECENT_PROJECTS);
+}
+
+fn find_estimate(estimates: &[Estimate], id: usize) -> Option<i64> {
+    estimates
+        .iter()
+        .find(|e| e.record_id == id)
+        .map(|e| e.estimate_seconds)
+}
+
+/// Describe how the duration of a record compares to its estimate, which
+/// counts as met within a minute.
+fn estimate_outcome(duration: i64, estimate: i64) -> String {
+    let clock = |s| format_duration(s, DurationFormat::Clock);
+    match duration - estimate {
+        d if d >= 60 => format!("Estimate of {} exceeded by {}", clock(estimate), clock(d)),
+        d if d <= -60 => format!("Estimate of {} under-run by {}", clock(estimate), clock(-d)),
+        _ => format!("Estimate of {} met", clock(estimate)),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct User {
     id: usize,
     username: String,
@@ -194,10 +782,78 @@ pub struct User {
     teams: Vec<Team>,
 }
 
-#[derive(Debug, Deserialize)]
+impl User {
+    pub fn print_table(&self) {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+        table.set_titles(row!["Attribute", "Value"]);
+        table.add_row(row!["ID", self.id]);
+        table.add_row(row!["Username", self.username]);
+        table.add_row(row!["Alias", self.alias.as_deref().unwrap_or("")]);
+        table.add_row(row!["Title", self.title.as_deref().unwrap_or("")]);
+        table.add_row(row!["Enabled", self.enabled]);
+        table.add_row(row!["Roles", self.roles.join(", ")]);
+        table.add_row(row!["Language", self.language]);
+        table.add_row(row!["Timezone", self.timezone]);
+        table.add_row(row![
+            "Teams",
+            self.teams
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        ]);
+        table.printstd();
+    }
+}
+
+/// A user as listed by Kimai, with less details than `User`
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ShortUser {
+    id: usize,
+    username: String,
+    alias: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Team {
     id: usize,
     name: String,
+    /// Members of the team, which are not given for the teams of a user
+    #[serde(default)]
+    members: Vec<TeamMember>,
+}
+
+impl Team {
+    pub fn print_table(&self) {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+        table.set_titles(row!["Attribute", "Value"]);
+        table.add_row(row!["ID", self.id]);
+        table.add_row(row!["Name", self.name]);
+        table.printstd();
+        println!();
+
+        let mut members = Table::new();
+        members.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+        members.set_titles(row!["ID", "Username", "Alias", "Teamlead"]);
+        for member in &self.members {
+            members.add_row(row![
+                r->member.user.id,
+                member.user.username,
+                member.user.alias.as_deref().unwrap_or(""),
+                if member.teamlead { "yes" } else { "" }
+            ]);
+        }
+        members.printstd();
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TeamMember {
+    user: ShortUser,
+    #[serde(default)]
+    teamlead: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -205,7 +861,29 @@ pub struct Customer {
     id: usize,
     name: String,
     visible: bool,
-    color: String,
+    color: Option<String>,
+    #[serde(default)]
+    country: Option<String>,
+    #[serde(default)]
+    currency: Option<String>,
+    #[serde(default)]
+    timezone: Option<String>,
+}
+
+impl Customer {
+    pub fn print_table(&self) {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+        table.set_titles(row!["Attribute", "Value"]);
+        table.add_row(row!["ID", self.id]);
+        table.add_row(row!["Name", self.name]);
+        table.add_row(row!["Visible", self.visible]);
+        table.add_row(row!["Color", self.color.as_deref().unwrap_or("")]);
+        table.add_row(row!["Country", self.country.as_deref().unwrap_or("")]);
+        table.add_row(row!["Currency", self.currency.as_deref().unwrap_or("")]);
+        table.add_row(row!["Timezone", self.timezone.as_deref().unwrap_or("")]);
+        table.printstd();
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -217,9 +895,51 @@ pub struct Project {
     parent_title: String,
     visible: bool,
     color: Option<String>,
+    /// Budget in the currency of the customer, which Kimai gives as 0 if
+    /// there is none
+    budget: Option<f64>,
+    /// Time budget in seconds, which Kimai gives as 0 if there is none
+    time_budget: Option<usize>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Project {
+    /// Get the budget, if the project has one.
+    fn budget(&self) -> Option<f64> {
+        self.budget.filter(|b| *b != 0.0)
+    }
+
+    /// Get the time budget in seconds, if the project has one.
+    fn time_budget(&self) -> Option<usize> {
+        self.time_budget.filter(|t| *t != 0)
+    }
+
+    pub fn print_table(&self) {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+        table.set_titles(row!["Attribute", "Value"]);
+        table.add_row(row!["ID", self.id]);
+        table.add_row(row!["Name", self.name]);
+        table.add_row(row!["Customer ID", self.customer]);
+        table.add_row(row!["Customer Name", self.parent_title]);
+        table.add_row(row!["Visible", self.visible]);
+        table.add_row(row!["Color", self.color.as_deref().unwrap_or("")]);
+        table.add_row(row![
+            "Budget",
+            self.budget()
+                .map(|b| format!("{:.2}", b))
+                .unwrap_or_default()
+        ]);
+        table.add_row(row![
+            "Time Budget",
+            self.time_budget()
+                .map(|t| format_hours(t as i64))
+                .unwrap_or_default()
+        ]);
+        table.printstd();
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ShortProject {
     id: usize,
     name: String,
@@ -239,7 +959,28 @@ pub struct Activity {
     color: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Activity {
+    pub fn print_table(&self) {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+        table.set_titles(row!["Attribute", "Value"]);
+        table.add_row(row!["ID", self.id]);
+        table.add_row(row!["Name", self.name]);
+        table.add_row(row![
+            "Project ID",
+            self.project.map(|p| p.to_string()).unwrap_or_default()
+        ]);
+        table.add_row(row![
+            "Project Name",
+            self.parent_title.as_deref().unwrap_or("")
+        ]);
+        table.add_row(row!["Visible", self.visible]);
+        table.add_row(row!["Color", self.color.as_deref().unwrap_or("")]);
+        table.printstd();
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ShortActivity {
     id: usize,
     name: String,
@@ -248,7 +989,8 @@ pub struct ShortActivity {
     project: Option<ShortProject>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TimesheetRecord {
     pub id: usize,
     description: Option<String>,
@@ -259,10 +1001,59 @@ pub struct TimesheetRecord {
     activity: usize,
     user: usize,
     tags: Vec<String>,
+    #[serde(default)]
+    exported: bool,
+    /// Whether the record is billable, which older Kimai versions don't tell
+    billable: Option<bool>,
+    /// Total amount of the record as calculated by Kimai
+    #[serde(default)]
+    rate: f64,
+    #[serde(default)]
+    hourly_rate: f64,
+    fixed_rate: Option<f64>,
+    #[serde(default)]
+    internal_rate: f64,
 }
 
 impl TimesheetRecord {
-    pub fn print_table(&self) {
+    /// Replace aliased tags by their canonical tag.
+    fn canonicalize_tags(&mut self, aliases: &BTreeMap<String, String>) {
+        self.tags = canonical_tags(&self.tags, aliases);
+    }
+
+    /// Print the record with the names of its project, activity and user,
+    /// and its tags canonicalized.
+    pub async fn print_table(&self, client: &KimaiClient) {
+        let (project, activity, user) = self.names(client).await;
+        let mut record = self.clone();
+        record.canonicalize_tags(&client.config.tag_aliases);
+        record.table(&project, &activity, &user).printstd();
+    }
+
+    /// Get the names of the record's project, activity and user. As the
+    /// record is already saved, any of them which can't be looked up is
+    /// given by ID instead of failing.
+    async fn names(&self, client: &KimaiClient) -> (String, String, String) {
+        let (project, activity, current_user) = tokio::join!(
+            client.get_project(self.project),
+            client.get_activity(self.activity),
+            client.get_current_user()
+        );
+        let user = match current_user {
+            Ok(u) if u.id == self.user => u.username,
+            _ => match client.get_user(self.user).await {
+                Ok(u) => u.username,
+                Err(_) => self.user.to_string(),
+            },
+        };
+        (
+            project.map_or_else(|_| self.project.to_string(), |p| p.name),
+            activity.map_or_else(|_| self.activity.to_string(), |a| a.name),
+            user,
+        )
+    }
+
+    fn table(&self, project: &str, activity: &str, user: &str) -> Table {
         let description = match &self.description {
             Some(d) => d,
             None => "",
@@ -271,10 +1062,9 @@ impl TimesheetRecord {
         table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
         table.set_titles(row!["Attribute", "Value"]);
         table.add_row(row!["ID", self.id]);
-        // TODO: resolve project, activity and user IDs to the actual names
-        table.add_row(row!["Project", self.project]);
-        table.add_row(row!["Activity", self.activity]);
-        table.add_row(row!["User", self.user]);
+        table.add_row(row!["Project", project]);
+        table.add_row(row!["Activity", activity]);
+        table.add_row(row!["User", user]);
         table.add_row(row!["Begin", self.begin]);
         if let Some(end) = self.end {
             table.add_row(row!["End", end]);
@@ -288,7 +1078,8 @@ impl TimesheetRecord {
         }
         table.add_row(row!["Description", description]);
         table.add_row(row!["Tags", self.tags.join(", ")]);
-        table.printstd();
+        table.add_row(row!["Exported", self.exported]);
+        table
     }
 }
 
@@ -302,598 +1093,7557 @@ struct NewTimesheetRecord {
     description: Option<String>,
     //user: usize,
     tags: Option<String>,
+    /// Values of custom fields, which servers without custom fields ignore
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    meta_fields: HashMap<String, String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct TimesheetRecordEntity {
-    id: usize,
-    begin: DateTime<Local>,
-    end: Option<DateTime<Local>>,
-    duration: i64,
+struct PatchTimesheetRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    activity: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    begin: Option<NaiveDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end: Option<NaiveDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
-    rate: f32,
-    internal_rate: f32,
-    #[serde(default)]
-    billable: bool,
-    project: ShortProject,
-    activity: ShortActivity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<String>,
 }
 
-fn get_headers(config: &Config) -> Result<header::HeaderMap, KimaiError> {
-    let mut headers = header::HeaderMap::new();
-    let auth_value = format!("Bearer {}", config.token);
-    headers.insert(
-        HeaderName::from_static("authorization"),
-        HeaderValue::from_str(&auth_value).unwrap(),
-    );
-    Ok(headers)
+#[derive(Debug, Serialize)]
+struct NewCustomer {
+    name: String,
+    visible: bool,
+    color: Option<String>,
+    country: String,
+    currency: String,
+    timezone: String,
 }
 
-async fn check_response(response: reqwest::Response) -> Result<reqwest::Response, KimaiError> {
-    if response.status().is_success() {
-        Ok(response)
-    } else {
-        Err(KimaiError::Api(response.text().await?))
+/// Changes to a customer, where `None` leaves a field untouched
+#[derive(Debug, Default, Serialize)]
+pub struct PatchCustomer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+impl PatchCustomer {
+    fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.visible.is_none()
+            && self.color.is_none()
+            && self.country.is_none()
+            && self.currency.is_none()
+            && self.timezone.is_none()
     }
 }
 
-async fn make_get_request<T>(
-    config: &Config,
-    api_endpoint: &str,
-    parameters: Option<HashMap<&str, String>>,
-) -> Result<T, KimaiError>
-where
-    T: for<'de> Deserialize<'de>,
-{
-    let url = format!("{}/{}", config.host, api_endpoint);
-    let mut request_builder = reqwest::Client::builder()
-        .default_headers(get_headers(config)?)
-        .build()?
-        .get(&url);
-    if let Some(p) = parameters {
-        request_builder = request_builder.query(&p);
-    }
-    Ok(check_response(request_builder.send().await?)
-        .await?
-        .json()
-        .await?)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NewProject {
+    name: String,
+    customer: usize,
+    visible: bool,
+    color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_budget: Option<usize>,
 }
 
-async fn make_post_request<T, V>(
-    config: &Config,
-    api_endpoint: &str,
-    body: T,
-    parameters: Option<HashMap<&str, String>>,
-) -> Result<V, KimaiError>
-where
-    T: Serialize,
-    V: for<'de> Deserialize<'de>,
-{
-    let url = format!("{}/{}", config.host, api_endpoint);
-    let mut request_builder = reqwest::Client::builder()
-        .default_headers(get_headers(config)?)
-        .build()?
-        .post(&url)
-        .json(&body);
-    if let Some(p) = parameters {
-        request_builder = request_builder.query(&p);
-    }
-    Ok(check_response(request_builder.send().await?)
-        .await?
-        .json()
-        .await?)
-}
-async fn make_patch_request<T, V>(
-    config: &Config,
-    api_endpoint: &str,
-    body: Option<T>,
-    parameters: Option<HashMap<&str, String>>,
-) -> Result<V, KimaiError>
-where
-    T: Serialize,
-    V: for<'de> Deserialize<'de>,
-{
-    let url = format!("{}/{}", config.host, api_endpoint);
-    let mut request_builder = reqwest::Client::builder()
-        .default_headers(get_headers(config)?)
-        .build()?
-        .patch(&url);
-    if let Some(b) = body {
-        request_builder = request_builder.json(&b);
-    }
-    if let Some(p) = parameters {
-        request_builder = request_builder.query(&p);
-    }
-    Ok(check_response(request_builder.send().await?)
-        .await?
-        .json()
-        .await?)
+/// Changes to a project, where `None` leaves a field untouched
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchProject {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_budget: Option<usize>,
 }
 
-/// Load a configuration file.
-///
-/// If `config_path` is `None`, it get's loaded from the XDG configuration
-/// folder.
-pub fn load_config(config_path: Option<String>) -> Result<Config, KimaiError> {
-    match config_path {
-        Some(p) => Config::from_path(Path::new(&p)),
-        None => Config::from_xdg(),
+impl PatchProject {
+    fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.customer.is_none()
+            && self.visible.is_none()
+            && self.color.is_none()
+            && self.budget.is_none()
+            && self.time_budget.is_none()
     }
 }
 
-/// Get all available customers
-pub async fn get_customers(
-    config: &Config,
-    term: Option<String>,
-) -> Result<Vec<Customer>, KimaiError> {
-    make_get_request(config, "api/customers", query!(("term", term))).await
+/// A new activity, which is global if it has no project. Some Kimai versions
+/// only accept a missing project as `null`, so it is always sent.
+#[derive(Debug, Serialize)]
+struct NewActivity {
+    name: String,
+    project: Option<usize>,
+    visible: bool,
+    color: Option<String>,
 }
 
-/// Get all available projects
-pub async fn get_projects(
-    config: &Config,
-    customers: Option<Vec<usize>>,
-    term: Option<String>,
-) -> Result<Vec<Project>, KimaiError> {
-    make_get_request(
-        config,
-        "api/projects",
-        query!(("customers", customers), ("term", term)),
-    )
-    .await
+/// Changes to an activity, where `None` leaves a field untouched
+#[derive(Debug, Default, Serialize)]
+pub struct PatchActivity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
 }
 
-/// Get all available activities
-pub async fn get_activities(
-    config: &Config,
-    projects: Option<Vec<usize>>,
-    term: Option<String>,
-) -> Result<Vec<Activity>, KimaiError> {
-    make_get_request(
-        config,
-        "api/activities",
-        query!(("projects", projects), ("term", term)),
-    )
-    .await
+impl PatchActivity {
+    fn is_empty(&self) -> bool {
+        self.name.is_none() && self.visible.is_none() && self.color.is_none()
+    }
 }
 
-/// Get a timesheet with all it's records
-pub async fn get_timesheet(
-    config: &Config,
-    user: Option<usize>,
-    customers: Option<Vec<usize>>,
-    projects: Option<Vec<usize>>,
-    activities: Option<Vec<usize>>,
-) -> Result<Vec<TimesheetRecord>, KimaiError> {
-    // TODO: Implemnt this to get the entire timesheet records
-    make_get_request(
-        config,
-        "api/timesheets",
-        query!(
-            ("user", user),
-            ("customers", customers),
-            ("projects", projects),
-            ("activities", activities)
-        ),
-    )
-    .await
+/// A tag as created by Kimai. Listing tags only gives their names.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Tag {
+    id: usize,
+    name: String,
+    /// Whether the tag is visible, which Kimai doesn't tell before 2.0
+    visible: Option<bool>,
+    color: Option<String>,
 }
 
-/// Begin a new timesheet record. If no begin time is given, the current time
-/// is used.
-pub async fn begin_timesheet_record(
-    config: &Config,
-    // TODO: find out why adding a user doesn't work
-    _user: usize,
-    project: usize,
-    activity: usize,
-    begin: DateTime<Local>,
-    description: Option<String>,
-    tags: Option<Vec<String>>,
-) -> Result<TimesheetRecord, KimaiError> {
-    let record = NewTimesheetRecord {
-        project,
-        activity,
-        begin: begin.naive_local(),
-        end: None,
-        description,
-        tags: tags.map(|t| t.join(",")),
-    };
-    make_post_request(config, "api/timesheets", record, None).await
+#[derive(Debug, Serialize)]
+struct NewTag {
+    name: String,
+    color: Option<String>,
 }
 
-/// End a given timesheet record. The current time is set as end time.
-pub async fn end_timesheet_record(
-    config: &Config,
+#[derive(Debug, Deserialize, Serialize)]
+pub struct InvoiceTemplate {
     id: usize,
-) -> Result<TimesheetRecord, KimaiError> {
-    make_patch_request::<Vec<String>, TimesheetRecord>(
-        config,
-        &format!("api/timesheets/{}/stop", id),
-        None,
-        None,
-    )
-    .await
+    name: String,
 }
 
-/// Get data of the user that is making logging in to make the request.
-pub async fn get_current_user(config: &Config) -> Result<User, KimaiError> {
-    make_get_request(config, "api/users/me", None).await
+/// Response of Kimai to an invoice request, if the invoice isn't sent
+/// directly
+#[derive(Debug, Deserialize)]
+struct InvoiceDownload {
+    url: String,
 }
 
-/// Log an entire timesheet record. If no end time is given, the current time
-/// is used.
-#[allow(clippy::too_many_arguments)]
-pub async fn log_timesheet_record(
-    config: &Config,
-    // TODO: find out why adding a user doesn't work
-    _user: usize,
-    project: usize,
-    activity: usize,
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimesheetRecordEntity {
+    id: usize,
     begin: DateTime<Local>,
     end: Option<DateTime<Local>>,
+    duration: i64,
     description: Option<String>,
-    tags: Option<Vec<String>>,
-) -> Result<TimesheetRecord, KimaiError> {
-    let record = NewTimesheetRecord {
-        project,
-        activity,
-        begin: begin.naive_local(),
-        end: end.map(|e| e.naive_local()),
-        description,
-        tags: tags.map(|t| t.join(",")),
-    };
-    make_post_request(config, "api/timesheets", record, None).await
+    rate: f32,
+    internal_rate: f32,
+    #[serde(default)]
+    billable: bool,
+    project: ShortProject,
+    activity: ShortActivity,
 }
 
-/// Get all currently active timesheet records
-pub async fn get_active_timesheet(
-    config: &Config,
-) -> Result<Vec<TimesheetRecordEntity>, KimaiError> {
-    make_get_request(&config, "api/timesheets/active", None).await
+fn get_headers(config: &Config) -> Result<header::HeaderMap, KimaiError> {
+    let mut headers = header::HeaderMap::new();
+    let auth_value = format!("Bearer {}", config.token);
+    headers.insert(
+        HeaderName::from_static("authorization"),
+        HeaderValue::from_str(&auth_value).unwrap(),
+    );
+    Ok(headers)
 }
 
-/// Get recent timesheet records
-pub async fn get_recent_timesheet(
-    config: &Config,
-    user: Option<usize>,
-    begin: Option<DateTime<Local>>,
-) -> Result<Vec<TimesheetRecordEntity>, KimaiError> {
-    make_get_request(
-        &config,
-        "api/timesheets/recent",
-        query!(("user", user), ("begin", begin)),
-    )
-    .await
+async fn check_response(response: reqwest::Response) -> Result<reqwest::Response, KimaiError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await?;
+    // Kimai explains errors in the message of a JSON body
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v.get("message")?.as_str().map(|m| m.to_string()))
+        .unwrap_or(body);
+    Err(KimaiError::Api(format!("{}: {}", status, message)))
 }
 
-/// Get the data of one given timesheet record
-pub async fn get_timesheet_record(
-    config: &Config,
-    id: usize,
-) -> Result<TimesheetRecord, KimaiError> {
-    make_get_request(&config, &format!("api/timesheets/{}", id), None).await
+/// A client for the Kimai API, which keeps one `reqwest::Client` with the
+/// authorization headers for all requests.
+pub struct KimaiClient {
+    config: Config,
+    client: reqwest::Client,
 }
 
-#[tokio::main]
-pub async fn print_customers(
-    config_path: Option<String>,
-    term: Option<String>,
-) -> Result<(), KimaiError> {
-    let config = load_config(config_path)?;
-    let customers = get_customers(&config, term).await?;
+impl KimaiClient {
+    pub fn new(config: Config) -> Result<Self, KimaiError> {
+        let client = reqwest::Client::builder()
+            .default_headers(get_headers(&config)?)
+            .build()?;
+        Ok(KimaiClient { config, client })
+    }
 
-    let mut table = Table::new();
-    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-    table.set_titles(row!["ID", "Name"]);
-    for customer in customers {
-        table.add_row(row![customer.id, customer.name]);
+    async fn make_get_request<T>(
+        &self,
+        api_endpoint: &str,
+        parameters: Option<Vec<(&str, String)>>,
+    ) -> Result<T, KimaiError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let url = format!("{}/{}", self.config.host, api_endpoint);
+        let mut request_builder = self.client.get(&url);
+        if let Some(p) = parameters {
+            request_builder = request_builder.query(&p);
+        }
+        Ok(check_response(request_builder.send().await?)
+            .await?
+            .json()
+            .await?)
     }
 
-    table.printstd();
+    /// Get a page of a listing together with the total number of items,
+    /// taken from the `X-Total-Count` header.
+    async fn make_get_page_request<T>(
+        &self,
+        api_endpoint: &str,
+        parameters: Option<Vec<(&str, String)>>,
+    ) -> Result<(Vec<T>, Option<usize>), KimaiError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let url = format!("{}/{}", self.config.host, api_endpoint);
+        let mut request_builder = self.client.get(&url);
+        if let Some(p) = parameters {
+            request_builder = request_builder.query(&p);
+        }
+        let response = check_response(request_builder.send().await?).await?;
+        let total = response
+            .headers()
+            .get("X-Total-Count")
+            .and_then(|c| c.to_str().ok())
+            .and_then(|c| c.parse().ok());
+        Ok((response.json().await?, total))
+    }
 
-    Ok(())
-}
+    async fn make_get_bytes_request(
+        &self,
+        api_endpoint: &str,
+        parameters: Option<Vec<(&str, String)>>,
+    ) -> Result<reqwest::Response, KimaiError> {
+        let url = format!("{}/{}", self.config.host, api_endpoint);
+        let mut request_builder = self.client.get(&url);
+        if let Some(p) = parameters {
+            request_builder = request_builder.query(&p);
+        }
+        check_response(request_builder.send().await?).await
+    }
 
-#[tokio::main]
-pub async fn print_projects(
-    config_path: Option<String>,
-    customers: Option<Vec<usize>>,
-    term: Option<String>,
-) -> Result<(), KimaiError> {
-    let config = load_config(config_path)?;
-    let projects = get_projects(&config, customers, term).await?;
+    async fn make_post_request<T, V>(
+        &self,
+        api_endpoint: &str,
+        body: T,
+        parameters: Option<Vec<(&str, String)>>,
+    ) -> Result<V, KimaiError>
+    where
+        T: Serialize,
+        V: for<'de> Deserialize<'de>,
+    {
+        let url = format!("{}/{}", self.config.host, api_endpoint);
+        let mut request_builder = self.client.post(&url).json(&body);
+        if let Some(p) = parameters {
+            request_builder = request_builder.query(&p);
+        }
+        Ok(check_response(request_builder.send().await?)
+            .await?
+            .json()
+            .await?)
+    }
 
-    let mut table = Table::new();
-    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-    table.set_titles(row!["ID", "Name", "Customer ID", "Customer Name"]);
-    for project in projects {
-        table.add_row(row![
-            r->project.id,
-            project.name,
-            r->project.customer,
-            project.parent_title
-        ]);
+    async fn make_patch_request<T, V>(
+        &self,
+        api_endpoint: &str,
+        body: Option<T>,
+        parameters: Option<Vec<(&str, String)>>,
+    ) -> Result<V, KimaiError>
+    where
+        T: Serialize,
+        V: for<'de> Deserialize<'de>,
+    {
+        let url = format!("{}/{}", self.config.host, api_endpoint);
+        let mut request_builder = self.client.patch(&url);
+        if let Some(b) = body {
+            request_builder = request_builder.json(&b);
+        }
+        if let Some(p) = parameters {
+            request_builder = request_builder.query(&p);
+        }
+        Ok(check_response(request_builder.send().await?)
+            .await?
+            .json()
+            .await?)
     }
 
-    table.printstd();
+    async fn make_delete_request(&self, api_endpoint: &str) -> Result<(), KimaiError> {
+        let url = format!("{}/{}", self.config.host, api_endpoint);
+        let request_builder = self.client.delete(&url);
+        check_response(request_builder.send().await?).await?;
+        Ok(())
+    }
 
-    Ok(())
-}
+    /// Get all available customers
+    pub async fn get_customers(&self, term: Option<String>) -> Result<Vec<Customer>, KimaiError> {
+        self.make_get_request("api/customers", query!(("term", term)))
+            .await
+    }
 
-#[tokio::main]
-pub async fn print_activities(
-    config_path: Option<String>,
-    projects: Option<Vec<usize>>,
-    term: Option<String>,
-) -> Result<(), KimaiError> {
-    let config = load_config(config_path)?;
-    let activities = get_activities(&config, projects, term).await?;
+    /// Get a given customer
+    pub async fn get_customer(&self, id: usize) -> Result<Customer, KimaiError> {
+        self.make_get_request(&format!("api/customers/{}", id), None)
+            .await
+    }
 
-    let mut table = Table::new();
-    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-    table.set_titles(row!["ID", "Name", "Project ID", "Project Name"]);
-    for activity in activities {
-        let project_str = match activity.project {
-            Some(p) => p.to_string(),
-            None => "".to_string(),
+    /// Create a new customer. Kimai requires its country, currency and
+    /// timezone.
+    pub async fn create_customer(
+        &self,
+        name: String,
+        visible: bool,
+        color: Option<String>,
+        country: String,
+        currency: String,
+        timezone: String,
+    ) -> Result<Customer, KimaiError> {
+        let customer = NewCustomer {
+            name,
+            visible,
+            color,
+            country,
+            currency,
+            timezone,
         };
-        table.add_row(row![
-            r->activity.id,
-            activity.name,
-            r->project_str,
-            activity.parent_title.unwrap_or_default()
-        ]);
+        self.make_post_request("api/customers", customer, None)
+            .await
     }
 
-    table.printstd();
+    /// Change the given fields of a customer, leaving the others untouched.
+    pub async fn update_customer(
+        &self,
+        id: usize,
+        changes: PatchCustomer,
+    ) -> Result<Customer, KimaiError> {
+        self.make_patch_request(&format!("api/customers/{}", id), Some(changes), None)
+            .await
+    }
 
-    Ok(())
-}
+    /// Delete a given customer
+    pub async fn delete_customer(&self, id: usize) -> Result<(), KimaiError> {
+        self.make_delete_request(&format!("api/customers/{}", id))
+            .await
+    }
 
-fn print_timesheets(records: &[TimesheetRecord]) {
-    let mut table = Table::new();
-    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-    table.set_titles(row![
-        "ID",
-        "Begin",
-        "End",
-        "Duration",
-        "Project",
-        "Activity",
-        "Description"
-    ]);
-    for record in records {
-        let description = match &record.description {
-            Some(d) => d.to_string(),
-            None => "".into(),
-        };
-        let end = match record.end {
-            Some(e) => e.format("%Y-%m-%d %H:%M").to_string(),
-            None => "".to_string(),
-        };
-        let d = chrono::Duration::seconds(record.duration);
-        let d_str = format!("{}:{:02}", d.num_hours(), d.num_minutes() % 60);
-        table.add_row(row![
-            r->record.id,
-            record.begin.format("%Y-%m-%d %H:%M"),
-            end,
-            r->d_str,
-            r->record.project,
-            r->record.activity,
-            description,
-        ]);
+    /// Get all available projects
+    pub async fn get_projects(
+        &self,
+        customers: Option<Vec<usize>>,
+        term: Option<String>,
+    ) -> Result<Vec<Project>, KimaiError> {
+        self.make_get_request(
+            "api/projects",
+            query!(("customers", customers), ("term", term)),
+        )
+        .await
     }
 
-    table.printstd();
-}
+    /// Get the data of one given project
+    pub async fn get_project(&self, id: usize) -> Result<Project, KimaiError> {
+        self.make_get_request(&format!("api/projects/{}", id), None)
+            .await
+    }
 
-fn print_timesheet_entities(records: &[TimesheetRecordEntity]) {
-    let mut table = Table::new();
-    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-    table.set_titles(row![
-        "ID",
-        "Begin",
-        "End",
-        "Duration",
-        "Project",
-        "Activity",
-        "Description"
-    ]);
-    for record in records {
-        let description = match &record.description {
-            Some(d) => d.to_string(),
-            None => "".into(),
-        };
-        let end = match record.end {
-            Some(e) => e.format("%Y-%m-%d %H:%M").to_string(),
-            None => "".to_string(),
+    /// Create a new project of a given customer
+    pub async fn create_project(
+        &self,
+        name: String,
+        customer: usize,
+        visible: bool,
+        color: Option<String>,
+        budget: Option<f64>,
+        time_budget: Option<usize>,
+    ) -> Result<Project, KimaiError> {
+        let project = NewProject {
+            name,
+            customer,
+            visible,
+            color,
+            budget,
+            time_budget,
         };
-        let d = chrono::Duration::seconds(record.duration);
-        let d_str = format!("{}:{:02}", d.num_hours(), d.num_minutes() % 60);
-        table.add_row(row![
-            r->record.id,
-            record.begin.format("%Y-%m-%d %H:%M"),
-            end,
-            r->d_str,
-            format!("{} ({})", record.project.id, record.project.name),
-            format!("{} ({})", record.activity.id, record.activity.name),
-            description,
-        ]);
+        self.make_post_request("api/projects", project, None).await
     }
 
-    table.printstd();
-}
+    /// Change the given fields of a project, leaving the others untouched.
+    pub async fn update_project(
+        &self,
+        id: usize,
+        changes: PatchProject,
+    ) -> Result<Project, KimaiError> {
+        self.make_patch_request(&format!("api/projects/{}", id), Some(changes), None)
+            .await
+    }
 
-#[tokio::main]
-pub async fn print_timesheet(
-    config_path: Option<String>,
-    user: Option<usize>,
-    customers: Option<Vec<usize>>,
-    projects: Option<Vec<usize>>,
-    activities: Option<Vec<usize>>,
-) -> Result<(), KimaiError> {
-    let config = load_config(config_path)?;
-    let timesheet_records = get_timesheet(&config, user, customers, projects, activities).await?;
+    /// Delete a given project
+    pub async fn delete_project(&self, id: usize) -> Result<(), KimaiError> {
+        self.make_delete_request(&format!("api/projects/{}", id))
+            .await
+    }
 
-    print_timesheets(&timesheet_records);
+    /// Get the data of one given activity
+    pub async fn get_activity(&self, id: usize) -> Result<Activity, KimaiError> {
+        self.make_get_request(&format!("api/activities/{}", id), None)
+            .await
+    }
 
-    Ok(())
-}
+    /// Create a new activity of a given project, or a global one without
+    /// a project
+    pub async fn create_activity(
+        &self,
+        name: String,
+        project: Option<usize>,
+        visible: bool,
+        color: Option<String>,
+    ) -> Result<Activity, KimaiError> {
+        let activity = NewActivity {
+            name,
+            project,
+            visible,
+            color,
+        };
+        self.make_post_request("api/activities", activity, None)
+            .await
+    }
 
-fn str_to_datetime(date_str: &str) -> Result<DateTime<Local>, KimaiError> {
-    match NaiveDateTime::parse_from_str(date_str, DATETIME_FORMAT) {
-        Ok(d) => Ok(Local.from_local_datetime(&d).unwrap()),
-        Err(_) => match NaiveTime::parse_from_str(date_str, TIME_FORMAT) {
-            Ok(t) => Ok(Local::today().and_time(t).unwrap()),
-            Err(e) => Err(KimaiError::from(e)),
-        },
+    /// Change the given fields of an activity, leaving the others untouched.
+    pub async fn update_activity(
+        &self,
+        id: usize,
+        changes: PatchActivity,
+    ) -> Result<Activity, KimaiError> {
+        self.make_patch_request(&format!("api/activities/{}", id), Some(changes), None)
+            .await
     }
-}
 
-fn get_datetime(datetime_str: Option<String>) -> Result<DateTime<Local>, KimaiError> {
-    match datetime_str {
-        Some(s) => str_to_datetime(&s),
-        None => {
-            let mut now = Local::now();
-            now = now - chrono::Duration::nanoseconds(now.timestamp_subsec_nanos() as i64);
-            Ok(now)
-        }
+    /// Delete a given activity
+    pub async fn delete_activity(&self, id: usize) -> Result<(), KimaiError> {
+        self.make_delete_request(&format!("api/activities/{}", id))
+            .await
     }
-}
 
-fn get_datetime_option(
-    datetime_str: Option<String>,
-) -> Result<Option<DateTime<Local>>, KimaiError> {
-    match datetime_str {
-        Some(s) => Ok(Some(str_to_datetime(&s)?)),
-        None => Ok(None),
+    /// Get all visible projects and activities, plus the ones of the records
+    /// which aren't visible anymore, resolved by their IDs.
+    async fn get_projects_and_activities_of(
+        &self,
+        records: &[TimesheetRecord],
+    ) -> Result<(Vec<Project>, Vec<Activity>), KimaiError> {
+        let (mut projects, mut activities) = tokio::try_join!(
+            self.get_projects(None, None),
+            self.get_activities(None, None)
+        )?;
+        let mut missing_projects: Vec<usize> = records
+            .iter()
+            .map(|r| r.project)
+            .filter(|id| !projects.iter().any(|p| p.id == *id))
+            .collect();
+        missing_projects.sort_unstable();
+        missing_projects.dedup();
+        let mut missing_activities: Vec<usize> = records
+            .iter()
+            .map(|r| r.activity)
+            .filter(|id| !activities.iter().any(|a| a.id == *id))
+            .collect();
+        missing_activities.sort_unstable();
+        missing_activities.dedup();
+
+        let (missing_projects, missing_activities) = tokio::try_join!(
+            get_by_ids(&missing_projects, self.config.resolve_concurrency, |id| {
+                self.get_project(id)
+            }),
+            get_by_ids(&missing_activities, self.config.resolve_concurrency, |id| {
+                self.get_activity(id)
+            })
+        )?;
+        projects.extend(missing_projects);
+        activities.extend(missing_activities);
+        Ok((projects, activities))
+    }
+
+    /// Get all available activities
+    pub async fn get_activities(
+        &self,
+        projects: Option<Vec<usize>>,
+        term: Option<String>,
+    ) -> Result<Vec<Activity>, KimaiError> {
+        self.make_get_request(
+            "api/activities",
+            query!(("projects", projects), ("term", term)),
+        )
+        .await
+    }
+
+    /// Get all available invoice templates
+    pub async fn get_invoice_templates(&self) -> Result<Vec<InvoiceTemplate>, KimaiError> {
+        self.make_get_request("api/invoice/templates", None).await
+    }
+
+    /// Generate an invoice for a customer with the given template. Kimai either
+    /// sends the file directly or a URL to download it from.
+    pub async fn get_invoice(
+        &self,
+        template: usize,
+        customer: usize,
+        begin: Option<DateTime<Local>>,
+        end: Option<DateTime<Local>>,
+    ) -> Result<Vec<u8>, KimaiError> {
+        let response = self
+            .make_get_bytes_request(
+                "api/invoice",
+                query!(
+                    ("template", Some(template)),
+                    ("customer", Some(customer)),
+                    ("begin", begin),
+                    ("end", end)
+                ),
+            )
+            .await?;
+        let is_json = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|c| c.to_str().ok())
+            .is_some_and(|c| c.starts_with("application/json"));
+        let response = match is_json {
+            true => {
+                let download: InvoiceDownload = response.json().await?;
+                let invalid = |e| KimaiError::Other(format!("Invalid download URL: {}", e));
+                let host = reqwest::Url::parse(&self.config.host).map_err(invalid)?;
+                let url = host.join(&download.url).map_err(invalid)?;
+                // Only send the token to the Kimai server itself
+                let request = match url.origin() == host.origin() {
+                    true => self.client.get(url),
+                    false => reqwest::Client::new().get(url),
+                };
+                check_response(request.send().await?).await?
+            }
+            false => response,
+        };
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Get a timesheet with all it's records matching the query
+    ///
+    /// If `query.page` is `None`, all pages are fetched one after another,
+    /// `query.size` records at a time, until `X-Total-Count` records are
+    /// fetched or a page is not full.
+    pub async fn get_timesheet(
+        &self,
+        query: TimesheetQuery,
+    ) -> Result<Vec<TimesheetRecord>, KimaiError> {
+        let size = match query.page {
+            Some(_) => query.size,
+            None => Some(query.size.unwrap_or(ALL_PAGES_SIZE)),
+        };
+        let mut records: Vec<TimesheetRecord> = Vec::new();
+        let mut current_page = query.page.unwrap_or(1);
+        loop {
+            let (batch, total): (Vec<TimesheetRecord>, Option<usize>) = self
+                .make_get_page_request(
+                    "api/timesheets",
+                    query!(
+                        ("user", query.user),
+                        ["customers[]", query.customers.clone()],
+                        ["projects[]", query.projects.clone()],
+                        ["activities[]", query.activities.clone()],
+                        ("begin", query.begin),
+                        ("end", query.end),
+                        ("exported", query.exported),
+                        ("billable", query.billable),
+                        ("tags", query.tags.clone()),
+                        ("term", query.term.clone()),
+                        ("page", Some(current_page)),
+                        ("size", size)
+                    ),
+                )
+                .await?;
+            let fetched = batch.len();
+            records.extend(batch);
+            if query.page.is_some()
+                || fetched == 0
+                || fetched < size.unwrap_or_default()
+                || total.is_some_and(|t| records.len() >= t)
+            {
+                break;
+            }
+            current_page += 1;
+        }
+        Ok(records)
+    }
+
+    /// Get the timesheet record of the current user that began last, if any
+    pub async fn get_last_timesheet_record(&self) -> Result<Option<TimesheetRecord>, KimaiError> {
+        let records: Vec<TimesheetRecord> = self
+            .make_get_request(
+                "api/timesheets",
+                query!(
+                    ("orderBy", Some("begin")),
+                    ("order", Some("DESC")),
+                    ("size", Some(1))
+                ),
+            )
+            .await?;
+        Ok(records.into_iter().next())
+    }
+
+    /// Begin a new timesheet record. If no begin time is given, the current time
+    /// is used.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn begin_timesheet_record(
+        &self,
+        // TODO: find out why adding a user doesn't work
+        _user: usize,
+        project: usize,
+        activity: usize,
+        begin: DateTime<Local>,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+        meta_fields: HashMap<String, String>,
+    ) -> Result<TimesheetRecord, KimaiError> {
+        let record = NewTimesheetRecord {
+            project,
+            activity,
+            begin: begin.naive_local(),
+            end: None,
+            description,
+            tags: tags.map(|t| t.join(",")),
+            meta_fields,
+        };
+        self.make_post_request("api/timesheets", record, None).await
+    }
+
+    /// End a given timesheet record. The current time is set as end time.
+    pub async fn end_timesheet_record(&self, id: usize) -> Result<TimesheetRecord, KimaiError> {
+        self.make_patch_request::<Vec<String>, TimesheetRecord>(
+            &format!("api/timesheets/{}/stop", id),
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Restart a timesheet record, which creates a new active record with the
+    /// same project, activity, description and tags.
+    pub async fn restart_timesheet_record(&self, id: usize) -> Result<TimesheetRecord, KimaiError> {
+        self.make_patch_request::<Vec<String>, TimesheetRecord>(
+            &format!("api/timesheets/{}/restart", id),
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Delete a timesheet record.
+    pub async fn delete_timesheet_record(&self, id: usize) -> Result<(), KimaiError> {
+        self.make_delete_request(&format!("api/timesheets/{}", id))
+            .await
+    }
+
+    /// Change the given fields of a timesheet record. Fields given as `None` are
+    /// left untouched.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn patch_timesheet_record(
+        &self,
+        id: usize,
+        project: Option<usize>,
+        activity: Option<usize>,
+        begin: Option<DateTime<Local>>,
+        end: Option<DateTime<Local>>,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<TimesheetRecord, KimaiError> {
+        let record = PatchTimesheetRecord {
+            project,
+            activity,
+            begin: begin.map(|b| b.naive_local()),
+            end: end.map(|e| e.naive_local()),
+            description,
+            tags: tags.map(|t| t.join(",")),
+        };
+        self.make_patch_request(&format!("api/timesheets/{}", id), Some(record), None)
+            .await
+    }
+
+    /// Get data of the user that is making logging in to make the request.
+    pub async fn get_current_user(&self) -> Result<User, KimaiError> {
+        self.make_get_request("api/users/me", None).await
+    }
+
+    /// Get all users visible to the current user
+    pub async fn get_users(&self, term: Option<String>) -> Result<Vec<ShortUser>, KimaiError> {
+        self.make_get_request("api/users", query!(("term", term)))
+            .await
+    }
+
+    /// Get the usernames by ID of all users visible to the current user.
+    /// Listing users needs admin rights, so without them only the current
+    /// user is known, and without any user the IDs have to do.
+    async fn get_usernames(&self) -> HashMap<usize, String> {
+        match self.get_users(None).await {
+            Ok(users) => users.into_iter().map(|u| (u.id, u.username)).collect(),
+            Err(_) => self
+                .get_current_user()
+                .await
+                .map(|u| HashMap::from([(u.id, u.username)]))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Get a given user
+    pub async fn get_user(&self, id: usize) -> Result<User, KimaiError> {
+        self.make_get_request(&format!("api/users/{}", id), None)
+            .await
+    }
+
+    /// Get all teams visible to the current user
+    pub async fn get_teams(&self) -> Result<Vec<Team>, KimaiError> {
+        self.make_get_request("api/teams", None).await
+    }
+
+    /// Get a given team with its members
+    pub async fn get_team(&self, id: usize) -> Result<Team, KimaiError> {
+        self.make_get_request(&format!("api/teams/{}", id), None)
+            .await
+    }
+
+    /// Log an entire timesheet record. If no end time is given, the current time
+    /// is used.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn log_timesheet_record(
+        &self,
+        // TODO: find out why adding a user doesn't work
+        _user: usize,
+        project: usize,
+        activity: usize,
+        begin: DateTime<Local>,
+        end: Option<DateTime<Local>>,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<TimesheetRecord, KimaiError> {
+        let record = NewTimesheetRecord {
+            project,
+            activity,
+            begin: begin.naive_local(),
+            end: end.map(|e| e.naive_local()),
+            description,
+            tags: tags.map(|t| t.join(",")),
+            meta_fields: HashMap::new(),
+        };
+        self.make_post_request("api/timesheets", record, None).await
+    }
+
+    /// Get all currently active timesheet records
+    pub async fn get_active_timesheet(&self) -> Result<Vec<TimesheetRecordEntity>, KimaiError> {
+        self.make_get_request("api/timesheets/active", None).await
+    }
+
+    /// Get recent timesheet records
+    pub async fn get_recent_timesheet(
+        &self,
+        user: Option<usize>,
+        begin: Option<DateTime<Local>>,
+    ) -> Result<Vec<TimesheetRecordEntity>, KimaiError> {
+        self.make_get_request(
+            "api/timesheets/recent",
+            query!(("user", user), ("begin", begin)),
+        )
+        .await
+    }
+
+    /// Get all timesheet records of the current user that might overlap with the
+    /// time span from `begin` to `end`.
+    async fn get_timesheet_around(
+        &self,
+        begin: DateTime<Local>,
+        end: DateTime<Local>,
+    ) -> Result<Vec<TimesheetRecord>, KimaiError> {
+        // Kimai filters by the records begin, so look a day back and ahead to
+        // catch records reaching into the given time span.
+        self.get_timesheet(TimesheetQuery {
+            begin: Some(begin - chrono::Duration::days(1)),
+            end: Some(end + chrono::Duration::days(1)),
+            ..TimesheetQuery::default()
+        })
+        .await
+    }
+
+    /// Get all timesheet records of the current user overlapping with the time
+    /// span from `begin` to `end`. An open end is treated as the current time.
+    async fn get_overlapping_timesheet_records(
+        &self,
+        begin: DateTime<Local>,
+        end: Option<DateTime<Local>>,
+        exclude: Option<usize>,
+    ) -> Result<Vec<TimesheetRecord>, KimaiError> {
+        let end = end.unwrap_or_else(Local::now);
+        let records = self.get_timesheet_around(begin, end).await?;
+        Ok(records
+            .into_iter()
+            .filter(|r| {
+                Some(r.id) != exclude
+                    && intervals_overlap(r.begin, r.end.unwrap_or_else(Local::now), begin, end)
+            })
+            .collect())
+    }
+
+    /// Get the data of one given timesheet record
+    pub async fn get_timesheet_record(&self, id: usize) -> Result<TimesheetRecord, KimaiError> {
+        self.make_get_request(&format!("api/timesheets/{}", id), None)
+            .await
+    }
+
+    /// Get the names of all tags
+    pub async fn get_tags(&self, term: Option<String>) -> Result<Vec<String>, KimaiError> {
+        self.make_get_request("api/tags", query!(("name", term)))
+            .await
+    }
+
+    /// Create a new tag
+    pub async fn create_tag(&self, name: String, color: Option<String>) -> Result<Tag, KimaiError> {
+        self.make_post_request("api/tags", NewTag { name, color }, None)
+            .await
+    }
+
+    /// Find tags with their IDs by name, which Kimai supports since 2.0
+    pub async fn find_tags(&self, name: Option<String>) -> Result<Vec<Tag>, KimaiError> {
+        self.make_get_request("api/tags/find", query!(("name", name)))
+            .await
+    }
+
+    /// Get the ID of the tag with exactly the given name
+    async fn get_tag_id(&self, name: &str) -> Result<usize, KimaiError> {
+        self.find_tags(Some(name.to_string()))
+            .await?
+            .into_iter()
+            .find(|t| t.name == name)
+            .map(|t| t.id)
+            .ok_or_else(|| KimaiError::Other(format!("No tag named {}!", name)))
+    }
+
+    /// Delete a given tag
+    pub async fn delete_tag(&self, id: usize) -> Result<(), KimaiError> {
+        self.make_delete_request(&format!("api/tags/{}", id)).await
+    }
+
+    /// Get the currency of each project's customer, by project ID
+    async fn get_project_currencies(&self) -> Result<HashMap<usize, String>, KimaiError> {
+        let customers = self.get_customers(None).await?;
+        Ok(self
+            .get_projects(None, None)
+            .await?
+            .into_iter()
+            .filter_map(|p| {
+                customers
+                    .iter()
+                    .find(|c| c.id == p.customer)
+                    .and_then(|c| c.currency.clone())
+                    .map(|c| (p.id, c))
+            })
+            .collect())
     }
 }
 
-#[tokio::main]
-pub async fn print_begin_timesheet_record(
+/// Environment variable selecting the profile of the config file to use
+pub const PROFILE_ENV_VAR: &str = "KIMAI_PROFILE";
+
+/// Load a configuration file.
+///
+/// If `config_path` is `None`, it get's loaded from the XDG configuration
+/// folder. Without a profile given, it is taken from `PROFILE_ENV_VAR` if set.
+pub fn load_config(
     config_path: Option<String>,
-    user: Option<usize>,
-    project: usize,
-    activity: usize,
-    begin: Option<String>,
-    description: Option<String>,
-    tags: Option<Vec<String>>,
+    profile: Option<String>,
+) -> Result<Config, KimaiError> {
+    let profile = profile_or_env(profile);
+    match config_path {
+        Some(p) => Config::from_path(Path::new(&p), profile.as_deref()),
+        None => Config::from_xdg(profile.as_deref()),
+    }
+}
+
+/// Name of the profile made up of the top-level fields of the config file
+const DEFAULT_PROFILE: &str = "default";
+
+/// Get the given profile, or the one of `PROFILE_ENV_VAR` if set.
+fn profile_or_env(profile: Option<String>) -> Option<String> {
+    profile.or_else(|| std::env::var(PROFILE_ENV_VAR).ok())
+}
+
+/// Load the configs of all profiles, starting with the default one. The
+/// environment variables override the given profile, like in `load_config`.
+pub fn load_profiles(
+    config_path: Option<String>,
+    profile: Option<String>,
+) -> Result<Vec<(String, Config)>, KimaiError> {
+    let profile = profile_or_env(profile);
+    match config_path {
+        Some(p) => Config::profiles_from_path(Path::new(&p), profile.as_deref()),
+        None => Config::profiles_from_xdg(profile.as_deref()),
+    }
+}
+
+/// Load the configs of all profiles, starting with the default one, and
+/// create a client for each.
+fn load_profile_clients(
+    config_path: Option<String>,
+    profile: Option<String>,
+) -> Result<Vec<(String, KimaiClient)>, KimaiError> {
+    load_profiles(config_path, profile)?
+        .into_iter()
+        .map(|(name, config)| Ok((name, KimaiClient::new(config)?)))
+        .collect()
+}
+
+/// Print the profile, host and user of the active configuration.
+pub async fn print_show_config(
+    config_path: Option<String>,
+    profile: Option<String>,
 ) -> Result<(), KimaiError> {
-    let config = load_config(config_path)?;
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let user = client.get_current_user().await?;
 
-    let record = begin_timesheet_record(
-        &config,
-        match user {
-            Some(u) => u,
-            None => get_current_user(&config).await?.id,
-        },
-        project,
-        activity,
-        get_datetime(begin)?,
-        description,
-        tags,
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.add_row(row!["Profile", client.config.profile]);
+    table.add_row(row!["Host", client.config.host]);
+    table.add_row(row!["User", user.username]);
+    table.printstd();
+
+    Ok(())
+}
+
+/// Check a configuration step by step and print the result of every check.
+/// The profile and the environment variables are taken into account like
+/// for every other command. Returns whether all checks passed.
+pub async fn print_validate_config(
+    config_path: Option<String>,
+    profile: Option<String>,
+) -> Result<bool, KimaiError> {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["Check", "Result", "Details"]);
+
+    let path = match config_path {
+        Some(p) => Ok(PathBuf::from(p)),
+        None => xdg_config_path(),
+    };
+    let checks = validate_config(
+        path,
+        profile_or_env(profile).as_deref(),
+        &EnvOverrides::from_env(),
+        &mut table,
     )
-    .await?;
+    .await;
+    table.printstd();
 
-    println!("Started new timesheet record:");
-    record.print_table();
+    Ok(checks)
+}
+
+async fn validate_config(
+    path: Result<PathBuf, KimaiError>,
+    profile: Option<&str>,
+    env: &EnvOverrides,
+    table: &mut Table,
+) -> bool {
+    macro_rules! check {
+        ($name:expr, $result:expr) => {
+            match $result {
+                Ok(v) => v,
+                Err(e) => {
+                    table.add_row(row![$name, Fr->"FAILED", e]);
+                    return false;
+                }
+            }
+        };
+    }
+
+    // Without a config file, the environment variables may suffice
+    let config_file = match path {
+        Ok(path) => {
+            let config_file = check!("Config file", ConfigFile::from_path(&path));
+            table.add_row(row!["Config file", Fg->"OK", path.display()]);
+            Ok(config_file)
+        }
+        Err(e) if env.host.is_some() && env.token.is_some() => {
+            table.add_row(row![
+                "Config file",
+                Fg->"OK",
+                format!("none, using {} and {}", HOST_ENV_VAR, TOKEN_ENV_VAR)
+            ]);
+            Err(e)
+        }
+        Err(e) => {
+            table.add_row(row!["Config file", Fr->"FAILED", e]);
+            return false;
+        }
+    };
+    let config = check!(
+        "Profile",
+        match config_file {
+            Ok(config_file) => Config::from_config_file_with_profile(config_file, profile, env),
+            Err(e) => Config::from_found_path(Err(e), profile, env),
+        }
+    );
+    table.add_row(row!["Profile", Fg->"OK", config.profile]);
+
+    let host = check!(
+        "Host",
+        match reqwest::Url::parse(&config.host) {
+            Ok(u) if u.scheme() == "http" || u.scheme() == "https" => Ok(u),
+            Ok(u) => Err(format!("unsupported scheme \"{}\"", u.scheme())),
+            Err(e) => Err(e.to_string()),
+        }
+    );
+    table.add_row(row!["Host", Fg->"OK", host]);
+
+    if config.token.is_empty() {
+        table.add_row(row!["Token", Fr->"FAILED", "token is empty"]);
+        return false;
+    }
+    table.add_row(row!["Token", Fg->"OK", ""]);
+
+    let client = check!("API", KimaiClient::new(config));
+    let user = check!("API", client.get_current_user().await);
+    table.add_row(row!["API", Fg->"OK", format!("logged in as {}", user.username)]);
+
+    true
+}
+
+/// Get the items with the given IDs one by one, sending at most
+/// `concurrency` requests at the same time.
+async fn get_by_ids<T, F, Fut>(
+    ids: &[usize],
+    concurrency: usize,
+    get: F,
+) -> Result<Vec<T>, KimaiError>
+where
+    F: Fn(usize) -> Fut,
+    Fut: std::future::Future<Output = Result<T, KimaiError>>,
+{
+    let semaphore = tokio::sync::Semaphore::new(concurrency.max(1));
+    futures::future::try_join_all(ids.iter().map(|&id| {
+        let semaphore = &semaphore;
+        let item = get(id);
+        async move {
+            let _permit = semaphore
+                .acquire()
+                .await
+                .map_err(|e| KimaiError::Other(e.to_string()))?;
+            item.await
+        }
+    }))
+    .await
+}
+
+/// Number of records per page when getting all pages of the timesheet
+const ALL_PAGES_SIZE: usize = 100;
+
+/// Replace aliased tags by their canonical tag, dropping tags which are
+/// given twice after that.
+fn canonical_tags(tags: &[String], aliases: &BTreeMap<String, String>) -> Vec<String> {
+    let mut canonical: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = aliases.get(tag).unwrap_or(tag);
+        if !canonical.contains(tag) {
+            canonical.push(tag.clone());
+        }
+    }
+    canonical
+}
+
+/// Parse a hex color like `#ff0000` into its RGB components.
+fn parse_hex_color(hex: &str) -> Option<(u8, u8, u8)> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    Some((
+        u8::from_str_radix(hex.get(0..2)?, 16).ok()?,
+        u8::from_str_radix(hex.get(2..4)?, 16).ok()?,
+        u8::from_str_radix(hex.get(4..6)?, 16).ok()?,
+    ))
+}
+
+/// Get the ANSI escape code setting the 24-bit foreground color to a hex
+/// color like `#ff0000`.
+fn hex_to_ansi(hex: &str) -> Option<String> {
+    let (r, g, b) = parse_hex_color(hex)?;
+    Some(format!("\x1b[38;2;{};{};{}m", r, g, b))
+}
+
+fn truecolor_supported() -> bool {
+    matches!(
+        std::env::var("COLORTERM").as_deref(),
+        Ok("truecolor") | Ok("24bit")
+    )
+}
+
+/// Color a text with a hex color from Kimai, if the terminal supports 24-bit
+/// colors.
+fn colorize(text: &str, color: Option<&str>) -> String {
+    match color.and_then(parse_hex_color) {
+        Some((r, g, b)) if truecolor_supported() => text.truecolor(r, g, b).to_string(),
+        _ => text.to_string(),
+    }
+}
+
+/// Lowest score of a fuzzy match to be shown
+const FUZZY_SCORE_THRESHOLD: usize = 60;
+
+/// Get the number of single character edits needed to turn `a` into `b`
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Score from 0 to 100 how well `term` matches any part of `name`, ignoring
+/// case. Parts of the length of `term` and one character more or less are
+/// compared, so single typos, missing or extra characters still match well.
+fn fuzzy_score(term: &str, name: &str) -> usize {
+    let term: Vec<char> = term.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    if term.is_empty() {
+        return 100;
+    }
+    let mut distance = levenshtein(&term, &name);
+    for length in term.len().saturating_sub(1)..=term.len() + 1 {
+        if length == 0 || length > name.len() {
+            continue;
+        }
+        for window in name.windows(length) {
+            distance = distance.min(levenshtein(&term, window));
+        }
+    }
+    100 * (term.len() - distance.min(term.len())) / term.len()
+}
+
+pub async fn print_invoice_templates(
+    config_path: Option<String>,
+    profile: Option<String>,
+    output: OutputFormat,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let templates = client.get_invoice_templates().await?;
+    if print_listing(&templates, output)? {
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.set_format(output.table_format());
+    table.set_titles(row!["ID", "Name"]);
+    for template in templates {
+        table.add_row(row![r->template.id, template.name]);
+    }
+
+    table.printstd();
+
+    Ok(())
+}
+
+/// Generate an invoice and save it to `out`, or `invoice.pdf` by default.
+pub async fn print_invoice(
+    config_path: Option<String>,
+    profile: Option<String>,
+    template: usize,
+    customer: usize,
+    from: Option<String>,
+    to: Option<String>,
+    out: Option<String>,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let invoice = client
+        .get_invoice(
+            template,
+            customer,
+            get_datetime_option(from)?,
+            get_datetime_option(to)?,
+        )
+        .await?;
+    let out = out.unwrap_or_else(|| "invoice.pdf".to_string());
+    fs::write(&out, invoice)?;
+    println!("Saved invoice to {}", out);
+
+    Ok(())
+}
+
+pub async fn print_customers(
+    config_path: Option<String>,
+    profile: Option<String>,
+    term: Option<String>,
+    fuzzy_term: Option<String>,
+    color: bool,
+    output: OutputFormat,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let customers = client.get_customers(term).await?;
+    let customers: Vec<(Customer, Option<usize>)> = match &fuzzy_term {
+        Some(t) => {
+            let mut matches: Vec<(Customer, Option<usize>)> = customers
+                .into_iter()
+                .map(|c| {
+                    let score = fuzzy_score(t, &c.name);
+                    (c, Some(score))
+                })
+                .filter(|(_, score)| score.unwrap_or(0) >= FUZZY_SCORE_THRESHOLD)
+                .collect();
+            matches.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+            matches
+        }
+        None => customers.into_iter().map(|c| (c, None)).collect(),
+    };
+    let (customers, scores): (Vec<Customer>, Vec<Option<usize>>) = customers.into_iter().unzip();
+    if print_listing(&customers, output)? {
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.set_format(output.table_format());
+    let mut titles = row!["ID", "Name"];
+    if fuzzy_term.is_some() {
+        titles.add_cell(Cell::new("Score"));
+    }
+    table.set_titles(titles);
+    for (customer, score) in customers.into_iter().zip(scores) {
+        let name = match color {
+            true => colorize(&customer.name, customer.color.as_deref()),
+            false => customer.name,
+        };
+        let mut row = row![customer.id, name];
+        if let Some(s) = score {
+            row.add_cell(Cell::new(&s.to_string()).style_spec("r"));
+        }
+        table.add_row(row);
+    }
+
+    table.printstd();
 
     Ok(())
 }
 
-#[tokio::main]
 #[allow(clippy::too_many_arguments)]
-pub async fn print_log_timesheet_record(
+pub async fn print_create_customer(
     config_path: Option<String>,
-    user: Option<usize>,
-    project: usize,
-    activity: usize,
-    begin: String,
-    end: Option<String>,
-    description: Option<String>,
-    tags: Option<Vec<String>>,
+    profile: Option<String>,
+    name: String,
+    visible: bool,
+    color: Option<String>,
+    country: String,
+    currency: String,
+    timezone: String,
 ) -> Result<(), KimaiError> {
-    let config = load_config(config_path)?;
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    client
+        .create_customer(name, visible, color, country, currency, timezone)
+        .await?
+        .print_table();
 
-    let record = log_timesheet_record(
-        &config,
-        match user {
-            Some(u) => u,
-            None => get_current_user(&config).await?.id,
-        },
-        project,
-        activity,
-        str_to_datetime(&begin)?,
-        get_datetime_option(end)?,
-        description,
-        tags,
-    )
-    .await?;
+    Ok(())
+}
 
-    println!("Logged new timesheet record:");
-    record.print_table();
+#[allow(clippy::too_many_arguments)]
+pub async fn print_update_customer(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+    name: Option<String>,
+    visible: Option<bool>,
+    color: Option<String>,
+    country: Option<String>,
+    currency: Option<String>,
+    timezone: Option<String>,
+) -> Result<(), KimaiError> {
+    let changes = PatchCustomer {
+        name,
+        visible,
+        color,
+        country,
+        currency,
+        timezone,
+    };
+    if changes.is_empty() {
+        return Err(KimaiError::Other("Nothing to change given!".to_string()));
+    }
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    client.update_customer(id, changes).await?.print_table();
 
     Ok(())
 }
 
-#[tokio::main]
-pub async fn print_end_timesheet_record(
+pub async fn print_delete_customer(
     config_path: Option<String>,
+    profile: Option<String>,
     id: usize,
+    force: bool,
+    no_prompt: bool,
 ) -> Result<(), KimaiError> {
-    let config = load_config(config_path)?;
-    let record = end_timesheet_record(&config, id).await?;
-    println!("Ended timesheet record:");
-    record.print_table();
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    if !force {
+        client.get_customer(id).await?.print_table();
+        if !confirm("Delete this customer?", no_prompt)? {
+            println!("Not deleting customer {}.", id);
+            return Ok(());
+        }
+    }
+    client.delete_customer(id).await?;
+    println!("Deleted customer {}.", id);
 
     Ok(())
 }
 
-#[tokio::main]
-pub async fn print_active_timesheet(config_path: Option<String>) -> Result<(), KimaiError> {
-    let config = load_config(config_path)?;
+pub async fn print_users(
+    config_path: Option<String>,
+    profile: Option<String>,
+    term: Option<String>,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let users = client.get_users(term).await?;
 
-    let records = get_active_timesheet(&config).await?;
-    print_timesheet_entities(&records);
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["ID", "Username", "Alias"]);
+    for user in users {
+        table.add_row(row![r->user.id, user.username, user.alias.unwrap_or_default()]);
+    }
+    table.printstd();
 
     Ok(())
 }
 
-#[tokio::main]
-pub async fn print_recent_timesheet(
+/// Print the details of a given user, or of the current one if no ID is
+/// given.
+pub async fn print_user(
     config_path: Option<String>,
-    user: Option<usize>,
-    begin: Option<String>,
+    profile: Option<String>,
+    id: Option<usize>,
 ) -> Result<(), KimaiError> {
-    let config = load_config(config_path)?;
-
-    let records =
-        get_recent_timesheet(&config, user, begin.map(|b| str_to_datetime(&b).unwrap())).await?;
-    print_timesheet_entities(&records);
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let user = match id {
+        Some(i) => client.get_user(i).await?,
+        None => client.get_current_user().await?,
+    };
+    user.print_table();
 
     Ok(())
 }
 
-#[tokio::main]
-pub async fn print_timesheet_record_status(
+pub async fn print_teams(
     config_path: Option<String>,
-    id: usize,
+    profile: Option<String>,
 ) -> Result<(), KimaiError> {
-    let config = load_config(config_path)?;
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let teams = client.get_teams().await?;
 
-    let record = get_timesheet_record(&config, id).await?;
-    record.print_table();
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["ID", "Name", "Members"]);
+    for team in teams {
+        table.add_row(row![r->team.id, team.name, r->team.members.len()]);
+    }
+    table.printstd();
 
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn basic_test() {
+pub async fn print_team(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    client.get_team(id).await?.print_table();
+
+    Ok(())
+}
+
+pub async fn print_tags(
+    config_path: Option<String>,
+    profile: Option<String>,
+    term: Option<String>,
+    canonical: bool,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let config = &client.config;
+    let tags = client.get_tags(term).await?;
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    match canonical {
+        true => {
+            table.set_titles(row!["Name", "Aliases"]);
+            for tag in canonical_tags(&tags, &config.tag_aliases) {
+                let aliases: Vec<&str> = tags
+                    .iter()
+                    .filter(|t| **t != tag && config.tag_aliases.get(*t) == Some(&tag))
+                    .map(|t| t.as_str())
+                    .collect();
+                table.add_row(row![tag, aliases.join(", ")]);
+            }
+        }
+        false => {
+            table.set_titles(row!["Name"]);
+            for tag in tags {
+                table.add_row(row![tag]);
+            }
+        }
+    }
+
+    table.printstd();
+
+    Ok(())
+}
+
+pub async fn print_create_tag(
+    config_path: Option<String>,
+    profile: Option<String>,
+    name: String,
+    color: Option<String>,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let tag = client.create_tag(name, color).await?;
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["ID", "Name", "Color"]);
+    table.add_row(row![r->tag.id, tag.name, tag.color.unwrap_or_default()]);
+    table.printstd();
+
+    Ok(())
+}
+
+pub async fn print_delete_tag(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: Option<usize>,
+    name: Option<String>,
+    force: bool,
+    no_prompt: bool,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let id = match (id, name) {
+        (Some(id), _) => id,
+        (None, Some(name)) => client.get_tag_id(&name).await?,
+        (None, None) => return Err(KimaiError::Other("No tag given!".to_string())),
+    };
+    if !force && !confirm(&format!("Delete tag {}?", id), no_prompt)? {
+        println!("Not deleting tag {}.", id);
+        return Ok(());
+    }
+    client.delete_tag(id).await?;
+    println!("Deleted tag {}.", id);
+
+    Ok(())
+}
+
+pub async fn print_projects(
+    config_path: Option<String>,
+    profile: Option<String>,
+    customers: Option<Vec<usize>>,
+    term: Option<String>,
+    color: bool,
+    output: OutputFormat,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let projects = client.get_projects(customers, term).await?;
+    if print_listing(&projects, output)? {
+        return Ok(());
+    }
+
+    // Budgets are only shown if any project has one
+    let show_budgets = projects
+        .iter()
+        .any(|p| p.budget().is_some() || p.time_budget().is_some());
+    let mut table = Table::new();
+    table.set_format(output.table_format());
+    let mut titles = row!["ID", "Name", "Customer ID", "Customer Name"];
+    if show_budgets {
+        titles.add_cell(Cell::new("Budget"));
+        titles.add_cell(Cell::new("Time Budget"));
+    }
+    table.set_titles(titles);
+    // Spent amount and seconds by project, to color the budgets by how much
+    // of them is used
+    let mut spent: HashMap<usize, (f64, i64)> = HashMap::new();
+    if show_budgets {
+        let records = client
+            .get_timesheet(TimesheetQuery {
+                user: Some(TimesheetUser::All),
+                projects: Some(
+                    projects
+                        .iter()
+                        .filter(|p| p.budget().is_some() || p.time_budget().is_some())
+                        .map(|p| p.id)
+                        .collect(),
+                ),
+                ..TimesheetQuery::default()
+            })
+            .await?;
+        for record in records {
+            let (amount, duration) = spent.entry(record.project).or_default();
+            *amount += record.rate;
+            *duration += record.duration;
+        }
+    }
+    let thresholds = client.config.budget_thresholds;
+    let budget_style = |share: f64| match thresholds.exceeded(share) {
+        Some((_, colored::Color::Red)) => "rFr",
+        Some(_) => "rFy",
+        None => "r",
+    };
+    for project in projects {
+        let (amount, duration) = spent.get(&project.id).copied().unwrap_or_default();
+        let name = match color {
+            true => colorize(&project.name, project.color.as_deref()),
+            false => project.name.clone(),
+        };
+        let mut row = row![
+            r->project.id,
+            name,
+            r->project.customer,
+            project.parent_title
+        ];
+        if show_budgets {
+            row.add_cell(match project.budget() {
+                Some(b) => Cell::new(&format!("{:.2}", b)).style_spec(budget_style(amount / b)),
+                None => Cell::new(""),
+            });
+            row.add_cell(match project.time_budget() {
+                Some(t) => Cell::new(&format_hours(t as i64))
+                    .style_spec(budget_style(duration as f64 / t as f64)),
+                None => Cell::new(""),
+            });
+        }
+        table.add_row(row);
+    }
+
+    table.printstd();
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn print_create_project(
+    config_path: Option<String>,
+    profile: Option<String>,
+    name: String,
+    customer: usize,
+    visible: bool,
+    color: Option<String>,
+    budget: Option<f64>,
+    time_budget: Option<usize>,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    client
+        .create_project(name, customer, visible, color, budget, time_budget)
+        .await?
+        .print_table();
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn print_update_project(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+    name: Option<String>,
+    customer: Option<usize>,
+    visible: Option<bool>,
+    color: Option<String>,
+    budget: Option<f64>,
+    time_budget: Option<usize>,
+) -> Result<(), KimaiError> {
+    let changes = PatchProject {
+        name,
+        customer,
+        visible,
+        color,
+        budget,
+        time_budget,
+    };
+    if changes.is_empty() {
+        return Err(KimaiError::Other("Nothing to change given!".to_string()));
+    }
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    client.update_project(id, changes).await?.print_table();
+
+    Ok(())
+}
+
+pub async fn print_delete_project(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+    force: bool,
+    no_prompt: bool,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    if !force {
+        client.get_project(id).await?.print_table();
+        if !confirm("Delete this project?", no_prompt)? {
+            println!("Not deleting project {}.", id);
+            return Ok(());
+        }
+    }
+    client.delete_project(id).await?;
+    println!("Deleted project {}.", id);
+
+    Ok(())
+}
+
+pub async fn print_activities(
+    config_path: Option<String>,
+    profile: Option<String>,
+    projects: Option<Vec<usize>>,
+    term: Option<String>,
+    color: bool,
+    output: OutputFormat,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let activities = client.get_activities(projects, term).await?;
+    if print_listing(&activities, output)? {
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.set_format(output.table_format());
+    table.set_titles(row!["ID", "Name", "Project ID", "Project Name"]);
+    for activity in activities {
+        let project_str = match activity.project {
+            Some(p) => p.to_string(),
+            None => "".to_string(),
+        };
+        let name = match color {
+            true => colorize(&activity.name, activity.color.as_deref()),
+            false => activity.name,
+        };
+        table.add_row(row![
+            r->activity.id,
+            name,
+            r->project_str,
+            activity.parent_title.unwrap_or_default()
+        ]);
+    }
+
+    table.printstd();
+
+    Ok(())
+}
+
+pub async fn print_create_activity(
+    config_path: Option<String>,
+    profile: Option<String>,
+    name: String,
+    project: Option<usize>,
+    visible: bool,
+    color: Option<String>,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    client
+        .create_activity(name, project, visible, color)
+        .await?
+        .print_table();
+
+    Ok(())
+}
+
+pub async fn print_update_activity(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+    name: Option<String>,
+    visible: Option<bool>,
+    color: Option<String>,
+) -> Result<(), KimaiError> {
+    let changes = PatchActivity {
+        name,
+        visible,
+        color,
+    };
+    if changes.is_empty() {
+        return Err(KimaiError::Other("Nothing to change given!".to_string()));
+    }
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    client.update_activity(id, changes).await?.print_table();
+
+    Ok(())
+}
+
+pub async fn print_delete_activity(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+    force: bool,
+    no_prompt: bool,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    if !force {
+        client.get_activity(id).await?.print_table();
+        if !confirm("Delete this activity?", no_prompt)? {
+            println!("Not deleting activity {}.", id);
+            return Ok(());
+        }
+    }
+    client.delete_activity(id).await?;
+    println!("Deleted activity {}.", id);
+
+    Ok(())
+}
+
+/// A column of the timesheet table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Id,
+    Begin,
+    End,
+    Duration,
+    /// The duration in decimal hours, next to the one in the chosen format
+    Hours,
+    /// The time since the previous record ended
+    Gap,
+    Project,
+    Activity,
+    Description,
+    Tags,
+    User,
+    Rate,
+    Amount,
+    Billable,
+    Exported,
+    PersonalNote,
+}
+
+impl Column {
+    const ALL: [Column; 16] = [
+        Column::Id,
+        Column::Begin,
+        Column::End,
+        Column::Duration,
+        Column::Hours,
+        Column::Gap,
+        Column::Project,
+        Column::Activity,
+        Column::Description,
+        Column::Tags,
+        Column::User,
+        Column::Rate,
+        Column::Amount,
+        Column::Billable,
+        Column::Exported,
+        Column::PersonalNote,
+    ];
+
+    /// Whether the cells hold numbers, which are aligned to the right
+    fn right_aligned(&self) -> bool {
+        matches!(
+            self,
+            Column::Id
+                | Column::Duration
+                | Column::Hours
+                | Column::Gap
+                | Column::Rate
+                | Column::Amount
+        )
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Column::Id => "ID",
+            Column::Begin => "Begin",
+            Column::End => "End",
+            Column::Duration => "Duration",
+            Column::Hours => "Hours",
+            Column::Gap => "Gap",
+            Column::Project => "Project",
+            Column::Activity => "Activity",
+            Column::Description => "Description",
+            Column::Tags => "Tags",
+            Column::User => "User",
+            Column::Rate => "Rate",
+            Column::Amount => "Amount",
+            Column::Billable => "Billable",
+            Column::Exported => "Exported",
+            Column::PersonalNote => "Personal Note",
+        }
+    }
+
+    fn cell(&self, record: &TimesheetRecord, format: &CellFormat) -> Cell {
+        match self {
+            Column::Id => Cell::new(&record.id.to_string()).style_spec("r"),
+            Column::Begin => Cell::new(
+                &match format.relative_to {
+                    Some(now) => humanize_datetime(record.begin, now),
+                    None => None,
+                }
+                .unwrap_or_else(|| record.begin.format(format.datetime_format()).to_string()),
+            ),
+            Column::End => Cell::new(&match record.end {
+                Some(e) => e.format(format.datetime_format()).to_string(),
+                None => "".to_string(),
+            }),
+            Column::Duration => {
+                Cell::new(&format_duration(record.duration, format.duration_format)).style_spec("r")
+            }
+            Column::Hours => Cell::new(&format_duration(record.duration, DurationFormat::Decimal))
+                .style_spec("r"),
+            Column::Gap => Cell::new(
+                &format
+                    .gaps
+                    .get(&record.id)
+                    .map(|g| format_duration(*g, format.duration_format))
+                    .unwrap_or_default(),
+            )
+            .style_spec("r"),
+            Column::Project => match format
+                .project_colors
+                .get(&record.project)
+                .and_then(|c| hex_to_ansi(c))
+            {
+                Some(ansi) => Cell::new(&format!("{}●\x1b[0m {}", ansi, format.project(record))),
+                None => Cell::new(&format.project(record)),
+            }
+            .style_spec(match format.color_by_project {
+                true => palette_color(record.project),
+                false => "",
+            }),
+            Column::Activity => {
+                Cell::new(&format.activity(record)).style_spec(match format.color_by_activity {
+                    true => palette_color(record.activity),
+                    false => "",
+                })
+            }
+            Column::Description => {
+                let description = record.description.as_deref().unwrap_or("");
+                match format.description_max_length {
+                    0 => Cell::new(description),
+                    max => Cell::new(&truncate_with_ellipsis(description, max)),
+                }
+            }
+            Column::Tags => Cell::new(&record.tags.join(", ")),
+            Column::User => match format.usernames.get(&record.user) {
+                Some(u) => Cell::new(u),
+                None => Cell::new(&record.user.to_string()).style_spec("r"),
+            },
+            Column::Rate => {
+                let currency = format.currency(record.project);
+                Cell::new(&match record.fixed_rate {
+                    Some(r) => format!("{} fixed", format_money(r, currency)),
+                    None => format!("{}/h", format_money(record.hourly_rate, currency)),
+                })
+                .style_spec("r")
+            }
+            Column::Amount => {
+                Cell::new(&format_money(record.rate, format.currency(record.project)))
+                    .style_spec("r")
+            }
+            Column::Billable => Cell::new(match record.billable {
+                Some(true) => "Yes",
+                Some(false) => "No",
+                None => "",
+            }),
+            Column::Exported => Cell::new(match record.exported {
+                true => "Yes",
+                false => "No",
+            }),
+            Column::PersonalNote => Cell::new(
+                format
+                    .personal_notes
+                    .get(&record.id)
+                    .map_or("", |n| n.note.as_str()),
+            ),
+        }
+    }
+}
+
+impl std::str::FromStr for Column {
+    type Err = KimaiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Column::ALL
+            .iter()
+            .find(|c| c.title().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| KimaiError::Other(format!("unknown column \"{}\"", s)))
+    }
+}
+
+/// How durations are displayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurationFormat {
+    /// Hours and minutes, like `2:30`
+    #[default]
+    Clock,
+    /// Decimal hours, like `2.50`
+    Decimal,
+}
+
+impl std::str::FromStr for DurationFormat {
+    type Err = KimaiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clock" => Ok(DurationFormat::Clock),
+            "decimal" => Ok(DurationFormat::Decimal),
+            _ => Err(KimaiError::Other(format!(
+                "unknown duration format \"{}\"",
+                s
+            ))),
+        }
+    }
+}
+
+fn format_duration(seconds: i64, format: DurationFormat) -> String {
+    match format {
+        DurationFormat::Clock => {
+            let d = chrono::Duration::seconds(seconds);
+            format!("{}:{:02}", d.num_hours(), d.num_minutes() % 60)
+        }
+        DurationFormat::Decimal => format!("{:.2}", seconds as f64 / 3600.0),
+    }
+}
+
+/// How listings are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    /// A table with columns only separated by spaces
+    TableNoBorder,
+    Json,
+    Csv,
+    /// Tab-separated values without quotes
+    Tsv,
+    /// iCalendar events
+    Ical,
+    /// One JSON object per line
+    Ndjson,
+    /// CSV in the format Clockify imports
+    ClockifyCsv,
+    /// CSV in the format of Toggl's detailed export
+    TogglCsv,
+    /// CSV in the format Harvest imports
+    HarvestCsv,
+    /// An Emacs org-mode table
+    Org,
+    /// A Markdown table
+    Markdown,
+}
+
+impl OutputFormat {
+    fn is_table(self) -> bool {
+        matches!(self, OutputFormat::Table | OutputFormat::TableNoBorder)
+    }
+
+    fn table_format(self) -> format::TableFormat {
+        match self {
+            OutputFormat::TableNoBorder => *format::consts::FORMAT_CLEAN,
+            _ => *format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR,
+        }
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = KimaiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "table-no-border" => Ok(OutputFormat::TableNoBorder),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "table-csv-like" => Ok(OutputFormat::Tsv),
+            "ical" => Ok(OutputFormat::Ical),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            "clockify-csv" => Ok(OutputFormat::ClockifyCsv),
+            "toggl-csv" => Ok(OutputFormat::TogglCsv),
+            "harvest-csv" => Ok(OutputFormat::HarvestCsv),
+            "org" => Ok(OutputFormat::Org),
+            "markdown" => Ok(OutputFormat::Markdown),
+            _ => Err(KimaiError::Other(format!(
+                "unknown output format \"{}\"",
+                s
+            ))),
+        }
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), KimaiError> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print every value as JSON on a line of its own.
+fn print_ndjson<T: Serialize>(values: &[T]) -> Result<(), KimaiError> {
+    for value in values {
+        println!("{}", serde_json::to_string(value)?);
+    }
+    Ok(())
+}
+
+/// Replace tabs and line breaks, which can't be quoted in TSV, by spaces.
+fn tsv_field(field: &str) -> String {
+    field.replace(['\t', '\r', '\n'], " ")
+}
+
+/// Print rows as CSV, or for `OutputFormat::Tsv` tab-separated without quotes.
+fn print_csv(
+    titles: &[&str],
+    rows: &[Vec<String>],
+    output: OutputFormat,
+) -> Result<(), KimaiError> {
+    let mut builder = csv::WriterBuilder::new();
+    if output == OutputFormat::Tsv {
+        builder.delimiter(b'\t').quote_style(csv::QuoteStyle::Never);
+    }
+    let mut writer = builder.from_writer(io::stdout());
+    writer.write_record(titles)?;
+    for row in rows {
+        match output {
+            OutputFormat::Tsv => writer.write_record(row.iter().map(|f| tsv_field(f)))?,
+            _ => writer.write_record(row)?,
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// An item of a listing which can be written as a CSV row
+trait WriteCsv {
+    /// Titles of the columns, in the order of `csv_row`
+    const COLUMNS: &'static [&'static str];
+
+    fn csv_row(&self) -> Vec<String>;
+}
+
+impl WriteCsv for Customer {
+    const COLUMNS: &'static [&'static str] = &["ID", "Name", "Visible", "Color", "Currency"];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.visible.to_string(),
+            self.color.clone().unwrap_or_default(),
+            self.currency.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+impl WriteCsv for Project {
+    const COLUMNS: &'static [&'static str] = &[
+        "ID",
+        "Name",
+        "Customer ID",
+        "Customer Name",
+        "Visible",
+        "Color",
+        "Budget",
+        "Time Budget",
+    ];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.customer.to_string(),
+            self.parent_title.clone(),
+            self.visible.to_string(),
+            self.color.clone().unwrap_or_default(),
+            self.budget().map(|b| b.to_string()).unwrap_or_default(),
+            self.time_budget()
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+impl WriteCsv for Activity {
+    const COLUMNS: &'static [&'static str] = &[
+        "ID",
+        "Name",
+        "Project ID",
+        "Project Name",
+        "Visible",
+        "Color",
+    ];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.project.map(|p| p.to_string()).unwrap_or_default(),
+            self.parent_title.clone().unwrap_or_default(),
+            self.visible.to_string(),
+            self.color.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+impl WriteCsv for InvoiceTemplate {
+    const COLUMNS: &'static [&'static str] = &["ID", "Name"];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![self.id.to_string(), self.name.clone()]
+    }
+}
+
+/// Print a listing in any format but a table. Returns `false` without
+/// printing anything if it is to be printed as a table.
+fn print_listing<T: Serialize + WriteCsv>(
+    items: &[T],
+    output: OutputFormat,
+) -> Result<bool, KimaiError> {
+    match output {
+        OutputFormat::Table | OutputFormat::TableNoBorder => return Ok(false),
+        OutputFormat::Json => print_json(items)?,
+        OutputFormat::Ndjson => print_ndjson(items)?,
+        OutputFormat::Csv | OutputFormat::Tsv => print_csv(
+            T::COLUMNS,
+            &items.iter().map(|i| i.csv_row()).collect::<Vec<_>>(),
+            output,
+        )?,
+        OutputFormat::Ical
+        | OutputFormat::ClockifyCsv
+        | OutputFormat::TogglCsv
+        | OutputFormat::HarvestCsv
+        | OutputFormat::Markdown
+        | OutputFormat::Org => {
+            return Err(KimaiError::Other(
+                "Only table, JSON and CSV output are supported for listings!".to_string(),
+            ))
+        }
+    }
+    Ok(true)
+}
+
+/// Settings for rendering the cells of a timesheet table
+#[derive(Debug, Default)]
+struct CellFormat {
+    duration_format: DurationFormat,
+    /// Currencies of the projects' customers, by project ID
+    currencies: HashMap<usize, String>,
+    /// Names of the users, by user ID
+    usernames: HashMap<usize, String>,
+    /// Format of the begin and end, `DATETIME_FORMAT` if not set
+    datetime_format: Option<String>,
+    /// Characters after which descriptions are cut off, 0 to show them fully
+    description_max_length: usize,
+    /// Hex colors of the projects, by project ID, to mark projects with a dot
+    project_colors: HashMap<usize, String>,
+    /// Separate the columns only by spaces
+    borderless: bool,
+    /// Color the project cells by project
+    color_by_project: bool,
+    /// Color the activity cells by activity
+    color_by_activity: bool,
+    /// Show recent begins relative to this time
+    relative_to: Option<DateTime<Local>>,
+    /// Personal notes, by record ID
+    personal_notes: BTreeMap<usize, PersonalNote>,
+    /// Seconds since the previous record ended, by record ID
+    gaps: HashMap<usize, i64>,
+    /// Names to show instead of the project IDs, by project ID
+    project_names: HashMap<usize, String>,
+    /// Names to show instead of the activity IDs, by activity ID
+    activity_names: HashMap<usize, String>,
+}
+
+/// Describe a time within the last week relative to `now`, like "2 hours 30
+/// minutes ago" or "yesterday at 14:30".
+fn humanize_datetime(datetime: DateTime<Local>, now: DateTime<Local>) -> Option<String> {
+    let plural = |n: i64, unit: &str| match n {
+        1 => format!("1 {}", unit),
+        n => format!("{} {}s", n, unit),
+    };
+    let elapsed = now - datetime;
+    let days = (now.date().naive_local() - datetime.date().naive_local()).num_days();
+    match days {
+        _ if elapsed < chrono::Duration::minutes(1) => Some("just now".to_string()),
+        0 if elapsed < chrono::Duration::hours(1) => {
+            Some(format!("{} ago", plural(elapsed.num_minutes(), "minute")))
+        }
+        0 => Some(match elapsed.num_minutes() % 60 {
+            0 => format!("{} ago", plural(elapsed.num_hours(), "hour")),
+            m => format!(
+                "{} {} ago",
+                plural(elapsed.num_hours(), "hour"),
+                plural(m, "minute")
+            ),
+        }),
+        1 => Some(format!("yesterday at {}", datetime.format(TIME_FORMAT))),
+        2..=6 => Some(format!(
+            "last {} at {}",
+            datetime.format("%A"),
+            datetime.format(TIME_FORMAT)
+        )),
+        _ => None,
+    }
+}
+
+/// Foreground colors of cells colored by ID, as prettytable style specifiers
+const PALETTE: [&str; 12] = [
+    "Fr", "Fg", "Fy", "Fb", "Fm", "Fc", "FR", "FG", "FY", "FB", "FM", "FC",
+];
+
+/// Get the color of an ID, which is the same on every run.
+fn palette_color(id: usize) -> &'static str {
+    PALETTE[id % PALETTE.len()]
+}
+
+impl CellFormat {
+    fn datetime_format(&self) -> &str {
+        self.datetime_format.as_deref().unwrap_or(DATETIME_FORMAT)
+    }
+
+    fn currency(&self, project: usize) -> Option<&str> {
+        self.currencies.get(&project).map(|c| c.as_str())
+    }
+
+    fn project(&self, record: &TimesheetRecord) -> String {
+        self.project_names
+            .get(&record.project)
+            .cloned()
+            .unwrap_or_else(|| record.project.to_string())
+    }
+
+    fn activity(&self, record: &TimesheetRecord) -> String {
+        self.activity_names
+            .get(&record.activity)
+            .cloned()
+            .unwrap_or_else(|| record.activity.to_string())
+    }
+}
+
+/// Format a monetary value with two decimal places, prefixed with the symbol
+/// of the given currency code.
+fn format_money(amount: f64, currency: Option<&str>) -> String {
+    match currency {
+        Some("EUR") => format!("€{:.2}", amount),
+        Some("USD") => format!("${:.2}", amount),
+        Some("GBP") => format!("£{:.2}", amount),
+        Some("JPY") => format!("¥{:.2}", amount),
+        Some(c) => format!("{} {:.2}", c, amount),
+        None => format!("{:.2}", amount),
+    }
+}
+
+fn print_timesheets(records: &[TimesheetRecord]) {
+    print_timesheet_table(records, &Column::ALL, &CellFormat::default(), false);
+}
+
+/// Get the contents of the cells of the records, without any styling.
+fn timesheet_rows(
+    records: &[TimesheetRecord],
+    columns: &[Column],
+    format: &CellFormat,
+) -> Vec<Vec<String>> {
+    records
+        .iter()
+        .map(|r| {
+            columns
+                .iter()
+                .map(|c| c.cell(r, format).get_content())
+                .collect()
+        })
+        .collect()
+}
+
+fn print_timesheet_csv(
+    records: &[TimesheetRecord],
+    columns: &[Column],
+    format: &CellFormat,
+    output: OutputFormat,
+) -> Result<(), KimaiError> {
+    print_csv(
+        &columns.iter().map(|c| c.title()).collect::<Vec<&str>>(),
+        &timesheet_rows(records, columns, format),
+        output,
+    )
+}
+
+fn print_timesheet_table(
+    records: &[TimesheetRecord],
+    columns: &[Column],
+    format: &CellFormat,
+    totals: bool,
+) {
+    let mut table = Table::new();
+    table.set_format(match format.borderless {
+        true => *format::consts::FORMAT_CLEAN,
+        false => *format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR,
+    });
+    table.set_titles(Row::new(
+        columns.iter().map(|c| Cell::new(c.title())).collect(),
+    ));
+    for record in records {
+        table.add_row(Row::new(
+            columns.iter().map(|c| c.cell(record, format)).collect(),
+        ));
+    }
+
+    if totals {
+        table.add_row(totals_row(records, columns, format));
+    }
+
+    table.printstd();
+}
+
+/// Build the footer row of a timesheet table, with the total duration and
+/// amount below their columns. The amount gets a currency only if all
+/// records share it.
+fn totals_row(records: &[TimesheetRecord], columns: &[Column], format: &CellFormat) -> Row {
+    let mut currencies = records.iter().map(|r| format.currency(r.project));
+    let first = currencies.next().flatten();
+    let currency = match currencies.all(|c| c == first) {
+        true => first,
+        false => None,
+    };
+    Row::new(
+        columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| match c {
+                Column::Duration => Cell::new(&format_duration(
+                    records.iter().map(|r| r.duration).sum(),
+                    format.duration_format,
+                ))
+                .style_spec("br"),
+                Column::Hours => Cell::new(&format_duration(
+                    records.iter().map(|r| r.duration).sum(),
+                    DurationFormat::Decimal,
+                ))
+                .style_spec("br"),
+                Column::Amount => Cell::new(&format_money(
+                    records.iter().map(|r| r.rate).sum(),
+                    currency,
+                ))
+                .style_spec("br"),
+                _ if i == 0 => Cell::new("Total").style_spec("b"),
+                _ => Cell::new(""),
+            })
+            .collect(),
+    )
+}
+
+/// Print expanded timesheet records. Kimai already embeds the project and
+/// activity in those, so their names are shown next to their IDs without
+/// further requests.
+///
+/// If `profiles` isn't empty, it holds the profile of each record, which is
+/// shown in a leading column.
+fn print_timesheet_entities(
+    records: &[TimesheetRecordEntity],
+    profiles: &[String],
+    estimates: &[Estimate],
+    idle_threshold: Option<chrono::Duration>,
+    hide_id: bool,
+    datetime_format: Option<&str>,
+) {
+    let datetime_format = datetime_format.unwrap_or(DATETIME_FORMAT);
+    let show_remaining = records
+        .iter()
+        .any(|r| find_estimate(estimates, r.id).is_some());
+    let is_idle = |r: &TimesheetRecordEntity| match idle_threshold {
+        Some(t) => r.end.is_none() && Local::now() - r.begin > t,
+        None => false,
+    };
+    let show_idle = records.iter().any(is_idle);
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    let mut titles = row![
+        "ID",
+        "Begin",
+        "End",
+        "Duration",
+        "Project",
+        "Activity",
+        "Description"
+    ];
+    if show_remaining {
+        titles.add_cell(Cell::new("Time remaining"));
+    }
+    if show_idle {
+        titles.add_cell(Cell::new("Idle"));
+    }
+    if hide_id {
+        titles.remove_cell(0);
+    }
+    if !profiles.is_empty() {
+        titles.insert_cell(0, Cell::new("Profile"));
+    }
+    table.set_titles(titles);
+    for (i, record) in records.iter().enumerate() {
+        let description = match &record.description {
+            Some(d) => d.to_string(),
+            None => "".into(),
+        };
+        let end = match record.end {
+            Some(e) => e.format(datetime_format).to_string(),
+            None => "".to_string(),
+        };
+        let d = chrono::Duration::seconds(record.duration);
+        let d_str = format!("{}:{:02}", d.num_hours(), d.num_minutes() % 60);
+        let mut row = row![
+            r->record.id,
+            record.begin.format(datetime_format),
+            end,
+            r->d_str,
+            format!("{} ({})", record.project.id, record.project.name),
+            format!("{} ({})", record.activity.id, record.activity.name),
+            description,
+        ];
+        if show_remaining {
+            let remaining = match find_estimate(estimates, record.id) {
+                Some(e) => {
+                    let remaining = e - (Local::now() - record.begin).num_seconds();
+                    match remaining < 0 {
+                        true => format!("-{}", format_duration(-remaining, DurationFormat::Clock)),
+                        false => format_duration(remaining, DurationFormat::Clock),
+                    }
+                }
+                None => "".to_string(),
+            };
+            row.add_cell(Cell::new(&remaining).style_spec("r"));
+        }
+        if show_idle {
+            row.add_cell(match is_idle(record) {
+                true => Cell::new("⚠ Idle?").style_spec("Fy"),
+                false => Cell::new(""),
+            });
+        }
+        if hide_id {
+            row.remove_cell(0);
+        }
+        if let Some(profile) = profiles.get(i) {
+            row.insert_cell(0, Cell::new(profile));
+        }
+        table.add_row(row);
+    }
+
+    table.printstd();
+}
+
+/// Options for processing and displaying a timesheet on the client side,
+/// after it has been fetched from Kimai.
+#[derive(Debug, Default)]
+pub struct TimesheetOptions {
+    /// IDs of activities whose records are left out
+    pub exclude_activities: Vec<usize>,
+    /// IDs of projects whose records are left out
+    pub exclude_projects: Vec<usize>,
+    /// Show the user column, which is hidden otherwise, unless it is hidden
+    /// by `hidden_columns` or `default_hidden_columns`
+    pub show_user: bool,
+    /// Show the hours column, which is hidden otherwise
+    pub hours_per_record: bool,
+    /// Show the gap column, which is hidden otherwise
+    pub show_gaps: bool,
+    /// Columns to leave out of the table, in addition to the ones configured
+    /// in `default_hidden_columns`
+    pub hidden_columns: Vec<Column>,
+    /// Columns of the table in their order, instead of the default ones
+    pub columns: Option<Vec<Column>>,
+    /// Only print the total duration of all records
+    pub total_only: bool,
+    /// Print the total duration per week instead of the records
+    pub agg_by_week: bool,
+    /// Print the total duration per weekday instead of the records
+    pub week_day_breakdown: bool,
+    /// Print the total duration per month instead of the records
+    pub agg_by_month: bool,
+    /// Print when the first record began and the last one ended
+    pub show_day_bounds: bool,
+    /// Warn about records overlapping each other
+    pub overlap_check: bool,
+    /// File to save the output to instead of printing it, for iCalendar
+    /// output
+    pub out: Option<String>,
+    /// Hours per working day, overriding the configured ones
+    pub working_hours: Option<f64>,
+    /// Working days per week, overriding the configured ones
+    pub working_days: Option<u32>,
+    /// Only show records with a higher ID than this one
+    pub since_record: Option<usize>,
+    /// Format of the date in the Harvest output
+    pub date_format: Option<String>,
+    /// Email address of the user for the Clockify output
+    pub user_email: Option<String>,
+    /// Only show records with descriptions containing this term, which is
+    /// also passed to Kimai as search `term`
+    pub full_text_search: Option<String>,
+    /// Color the project cells, with the same color for the same project
+    pub color_by_project: bool,
+    /// Color the activity cells, with the same color for the same activity
+    pub color_by_activity: bool,
+    /// Show the begin of records from the last week relative to now
+    pub human_duration: bool,
+    /// Print the total duration per project after the records
+    pub project_summary: bool,
+    /// Print the total duration per customer after the records
+    pub customer_summary: bool,
+    /// Print a summary of the amount to invoice for records not exported yet
+    /// instead of the records
+    pub invoice_preview: bool,
+    /// Whether to print a footer row with the totals, by default only if
+    /// amounts are shown
+    pub totals: Option<bool>,
+    pub output: OutputFormat,
+    pub duration_format: DurationFormat,
+    /// Format of the begin and end, overriding the configured one
+    pub datetime_format: Option<String>,
+    /// Display each duration rounded up to a multiple of this many minutes
+    pub round_up_to: Option<u64>,
+    /// Characters after which descriptions are cut off in the table,
+    /// overriding the configured length, 0 to show them fully
+    pub description_max_length: Option<usize>,
+    /// Only show records with the given billable state
+    pub billable: Option<bool>,
+    /// Only show records with any of these tags
+    pub tags: Option<Vec<String>>,
+    /// Page of records to get, or all pages if `None`. Ignored when
+    /// summarizing, which always covers all pages.
+    pub page: Option<usize>,
+    /// Number of records per page
+    pub page_size: Option<usize>,
+}
+
+impl TimesheetOptions {
+    /// Whether totals, aggregations or summaries of the records are printed,
+    /// which need all of them instead of a single page.
+    fn summarizes(&self) -> bool {
+        self.total_only
+            || self.agg_by_week
+            || self.week_day_breakdown
+            || self.agg_by_month
+            || self.show_day_bounds
+            || self.project_summary
+            || self.customer_summary
+            || self.invoice_preview
+            || self.totals == Some(true)
+    }
+
+    /// Whether records are left out on the client side, which needs all of
+    /// them instead of a single page.
+    fn filters_client_side(&self) -> bool {
+        !self.exclude_activities.is_empty()
+            || !self.exclude_projects.is_empty()
+            || self.since_record.is_some()
+    }
+}
+
+/// Sum up the duration and count the records per ISO week, by year and week.
+fn weekly_totals(records: &[TimesheetRecord]) -> BTreeMap<(i32, u32), (i64, usize)> {
+    let mut weeks: BTreeMap<(i32, u32), (i64, usize)> = BTreeMap::new();
+    for record in records {
+        let week = record.begin.iso_week();
+        let entry = weeks.entry((week.year(), week.week())).or_insert((0, 0));
+        entry.0 += record.duration;
+        entry.1 += 1;
+    }
+    weeks
+}
+
+/// Print the total duration and number of records per ISO week, together with
+/// a running total.
+fn print_weekly_aggregation(records: &[TimesheetRecord], duration_format: DurationFormat) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row![
+        "Week",
+        "Date Range",
+        "Total Hours",
+        "Record Count",
+        "Cumulative"
+    ]);
+    let mut cumulative = 0;
+    for ((year, week), (duration, count)) in weekly_totals(records) {
+        cumulative += duration;
+        let monday = NaiveDate::from_isoywd(year, week, Weekday::Mon);
+        let sunday = NaiveDate::from_isoywd(year, week, Weekday::Sun);
+        table.add_row(row![
+            format!("{}-W{:02}", year, week),
+            format!("{} – {}", monday, sunday),
+            r->format_duration(duration, duration_format),
+            r->count,
+            r->format_duration(cumulative, duration_format),
+        ]);
+    }
+
+    table.printstd();
+}
+
+/// Get the earliest begin and the latest end of the records, where running
+/// records end `now`.
+fn day_bounds(
+    records: &[TimesheetRecord],
+    now: DateTime<Local>,
+) -> Option<(DateTime<Local>, DateTime<Local>)> {
+    let first = records.iter().map(|r| r.begin).min()?;
+    let last = records.iter().map(|r| r.end.unwrap_or(now)).max()?;
+    Some((first, last))
+}
+
+/// Hours and days expected to be worked, against which tracked time is
+/// compared
+#[derive(Debug, Clone, PartialEq)]
+struct WorkingTimeModel {
+    hours_per_day: f64,
+    /// Number of working days per week, counted from Monday
+    days_per_week: u32,
+    /// Days off which would otherwise be working days
+    holidays: Vec<NaiveDate>,
+}
+
+impl Default for WorkingTimeModel {
+    fn default() -> Self {
+        WorkingTimeModel {
+            hours_per_day: 8.0,
+            days_per_week: 5,
+            holidays: Vec::new(),
+        }
+    }
+}
+
+impl WorkingTimeModel {
+    fn is_working_day(&self, weekday: Weekday) -> bool {
+        weekday.num_days_from_monday() < self.days_per_week
+    }
+
+    /// Get the seconds expected to be worked in the given number of days.
+    fn target_seconds(&self, working_days: i64) -> i64 {
+        (self.hours_per_day * 3600.0 * working_days as f64).round() as i64
+    }
+
+    /// Count the working days from `begin` up to, but not including, `end`.
+    fn working_days(&self, begin: NaiveDate, end: NaiveDate) -> i64 {
+        let mut days = 0;
+        let mut day = begin;
+        while day < end {
+            if self.is_working_day(day.weekday()) && !self.holidays.contains(&day) {
+                days += 1;
+            }
+            day = day.succ();
+        }
+        days
+    }
+}
+
+/// Width of progress bars in characters
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+/// Draw a bar filled to the given fraction, which is capped at full.
+fn progress_bar(fraction: f64, width: usize) -> String {
+    let filled = ((fraction.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// Print the total duration per weekday, compared to the daily target summed
+/// over all weeks having records. Days off have no target.
+fn print_week_day_breakdown(
+    records: &[TimesheetRecord],
+    duration_format: DurationFormat,
+    model: &WorkingTimeModel,
+) {
+    let mut totals = [0i64; 7];
+    let mut weeks = Vec::new();
+    for record in records {
+        totals[record.begin.weekday().num_days_from_monday() as usize] += record.duration;
+        let week = record.begin.iso_week();
+        if !weeks.contains(&week) {
+            weeks.push(week);
+        }
+    }
+    let target = model.target_seconds(weeks.len() as i64);
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["Day", "Hours", "Target", ""]);
+    let weekdays = std::iter::successors(Some(Weekday::Mon), |d| Some(d.succ()));
+    for (weekday, total) in weekdays.zip(totals.iter()) {
+        match model.is_working_day(weekday) {
+            false => table.add_row(row![
+                weekday,
+                r->format_duration(*total, duration_format),
+                "",
+                "",
+            ]),
+            true => table.add_row(row![
+                weekday,
+                r->format_duration(*total, duration_format),
+                r->format_duration(target, duration_format),
+                progress_bar(*total as f64 / target.max(1) as f64, PROGRESS_BAR_WIDTH),
+            ]),
+        };
+    }
+
+    table.printstd();
+}
+
+/// Print the total duration and number of records per calendar month,
+/// compared to the daily target on every working day of the month.
+fn print_monthly_aggregation(
+    records: &[TimesheetRecord],
+    duration_format: DurationFormat,
+    model: &WorkingTimeModel,
+) {
+    let mut months: BTreeMap<(i32, u32), (i64, usize)> = BTreeMap::new();
+    for record in records {
+        let entry = months
+            .entry((record.begin.year(), record.begin.month()))
+            .or_insert((0, 0));
+        entry.0 += record.duration;
+        entry.1 += 1;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row![
+        "Month",
+        "Record Count",
+        "Total Hours",
+        "Target Hours",
+        "Delta",
+        ""
+    ]);
+    for ((year, month), (duration, count)) in months {
+        let target = model.target_seconds(model.working_days(
+            first_of_month(year, month).naive_local(),
+            first_of_month(year, month + 1).naive_local(),
+        ));
+        table.add_row(row![
+            format!("{}-{:02}", year, month),
+            r->count,
+            r->format_duration(duration, duration_format),
+            r->format_duration(target, duration_format),
+            r->format_delta(duration - target),
+            progress_bar(duration as f64 / target.max(1) as f64, PROGRESS_BAR_WIDTH),
+        ]);
+    }
+
+    table.printstd();
+}
+
+/// Print the total duration of a month compared to the target over its
+/// working days.
+fn print_month_summary(
+    records: &[TimesheetRecord],
+    begin: NaiveDate,
+    end: NaiveDate,
+    duration_format: DurationFormat,
+    model: &WorkingTimeModel,
+) {
+    let total: i64 = records.iter().map(|r| r.duration).sum();
+    let working_days = model.working_days(begin, end);
+    let target = model.target_seconds(working_days);
+    let completion = total as f64 / target.max(1) as f64 * 100.0;
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.add_row(row!["Total Hours", r->format_duration(total, duration_format)]);
+    table.add_row(row!["Working Days in Period", r->working_days]);
+    table.add_row(row!["Target Hours", r->format_duration(target, duration_format)]);
+    table.add_row(row![
+        match total < target {
+            true => "Deficit",
+            false => "Surplus",
+        },
+        r->format_delta(total - target),
+    ]);
+    table.add_row(row!["Completion", r->format!("{:.1}%", completion)]);
+    table.printstd();
+    println!("You've worked {:.1}% of your monthly target.", completion);
+}
+
+/// Get the record count and total duration per key, sorted by the duration
+/// in descending order.
+fn totals_by<K: Ord>(
+    records: &[TimesheetRecord],
+    key: impl Fn(&TimesheetRecord) -> K,
+) -> Vec<(K, usize, i64)> {
+    let mut groups: BTreeMap<K, (usize, i64)> = BTreeMap::new();
+    for record in records {
+        let entry = groups.entry(key(record)).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += record.duration;
+    }
+    let mut totals: Vec<(K, usize, i64)> = groups
+        .into_iter()
+        .map(|(k, (count, duration))| (k, count, duration))
+        .collect();
+    totals.sort_by_key(|t| std::cmp::Reverse(t.2));
+    totals
+}
+
+/// Get the record count and total duration per project, sorted by the
+/// duration in descending order.
+fn project_totals(records: &[TimesheetRecord]) -> Vec<(usize, usize, i64)> {
+    totals_by(records, |r| r.project)
+}
+
+fn print_project_summary(
+    records: &[TimesheetRecord],
+    projects: &[Project],
+    duration_format: DurationFormat,
+) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["Project ID", "Name", "Record Count", "Total Duration"]);
+    for (id, count, duration) in project_totals(records) {
+        let name = projects
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.name.as_str())
+            .unwrap_or("");
+        table.add_row(row![
+            r->id,
+            name,
+            r->count,
+            r->format_duration(duration, duration_format),
+        ]);
+    }
+    table.printstd();
+}
+
+fn print_customer_summary(
+    records: &[TimesheetRecord],
+    projects: &[Project],
+    customers: &[Customer],
+    duration_format: DurationFormat,
+) {
+    let totals = totals_by(records, |r| {
+        projects
+            .iter()
+            .find(|p| p.id == r.project)
+            .and_then(|p| customers.iter().find(|c| c.id == p.customer))
+            .map(|c| c.name.clone())
+    });
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["Customer Name", "Record Count", "Total Duration"]);
+    for (name, count, duration) in totals {
+        table.add_row(row![
+            name.unwrap_or_default(),
+            r->count,
+            r->format_duration(duration, duration_format),
+        ]);
+    }
+    table.printstd();
+}
+
+/// A line of an invoice, summing up the records of one activity and rate
+#[derive(Debug, PartialEq, Serialize)]
+struct InvoiceLine {
+    activity: String,
+    hours: f64,
+    rate: f64,
+    fixed_rate: bool,
+    amount: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct InvoicePreview {
+    currency: Option<String>,
+    lines: Vec<InvoiceLine>,
+    total: f64,
+}
+
+impl InvoicePreview {
+    fn new(records: &[TimesheetRecord], activities: &[Activity], currency: Option<String>) -> Self {
+        // Rates are keyed in cents, since floats can't be ordered
+        let mut lines: BTreeMap<(usize, bool, i64), (i64, f64, f64)> = BTreeMap::new();
+        for record in records {
+            let rate = record.fixed_rate.unwrap_or(record.hourly_rate);
+            let key = (
+                record.activity,
+                record.fixed_rate.is_some(),
+                (rate * 100.0).round() as i64,
+            );
+            let line = lines.entry(key).or_insert((0, rate, 0.0));
+            line.0 += record.duration;
+            line.2 += record.rate;
+        }
+        let lines: Vec<InvoiceLine> = lines
+            .into_iter()
+            .map(
+                |((activity, fixed_rate, _), (duration, rate, amount))| InvoiceLine {
+                    activity: activities
+                        .iter()
+                        .find(|a| a.id == activity)
+                        .map(|a| a.name.clone())
+                        .unwrap_or_else(|| activity.to_string()),
+                    hours: duration as f64 / 3600.0,
+                    rate,
+                    fixed_rate,
+                    amount,
+                },
+            )
+            .collect();
+        InvoicePreview {
+            currency,
+            total: lines.iter().map(|l| l.amount).sum(),
+            lines,
+        }
+    }
+
+    fn print(&self, output: OutputFormat) -> Result<(), KimaiError> {
+        let currency = self.currency.as_deref();
+        let rate = |line: &InvoiceLine| match line.fixed_rate {
+            true => format!("{} fixed", format_money(line.rate, currency)),
+            false => format!("{}/h", format_money(line.rate, currency)),
+        };
+        match output {
+            OutputFormat::Table | OutputFormat::TableNoBorder => {
+                let mut table = Table::new();
+                table.set_format(output.table_format());
+                table.set_titles(row!["Activity", "Hours", "Rate", "Amount"]);
+                for line in &self.lines {
+                    table.add_row(row![
+                        line.activity,
+                        r->format!("{:.2}", line.hours),
+                        r->rate(line),
+                        r->format_money(line.amount, currency),
+                    ]);
+                }
+                table.add_row(row![b->"Total", "", "", br->format_money(self.total, currency)]);
+                table.printstd();
+                Ok(())
+            }
+            OutputFormat::Json => print_json(self),
+            OutputFormat::Ndjson => print_ndjson(&self.lines),
+            OutputFormat::Csv | OutputFormat::Tsv => {
+                let mut rows: Vec<Vec<String>> = self
+                    .lines
+                    .iter()
+                    .map(|l| {
+                        vec![
+                            l.activity.clone(),
+                            format!("{:.2}", l.hours),
+                            rate(l),
+                            format!("{:.2}", l.amount),
+                        ]
+                    })
+                    .collect();
+                rows.push(vec![
+                    "Total".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    format!("{:.2}", self.total),
+                ]);
+                print_csv(&["Activity", "Hours", "Rate", "Amount"], &rows, output)
+            }
+            OutputFormat::Ical
+            | OutputFormat::ClockifyCsv
+            | OutputFormat::TogglCsv
+            | OutputFormat::HarvestCsv
+            | OutputFormat::Markdown
+            | OutputFormat::Org => Err(KimaiError::Other(
+                "Only table, JSON and CSV output are supported for invoice previews!".to_string(),
+            )),
+        }
+    }
+}
+
+/// A period of time to get the timesheet for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// The current week, starting on monday
+    ThisWeek,
+    /// The current calendar month up to the end of today
+    ThisMonth,
+    /// The calendar month before the current one
+    LastMonth,
+    /// The current calendar quarter
+    ThisQuarter,
+    /// The calendar quarter before the current one
+    LastQuarter,
+}
+
+/// Get the first day of the given month, where months past december roll over
+/// into the next year.
+fn first_of_month(year: i32, month: u32) -> Date<Local> {
+    let months = year * 12 + month as i32 - 1;
+    Local.ymd(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1)
+}
+
+/// Get the first moment of the given day. Days that don't begin at midnight
+/// because of a DST transition begin at the earliest valid local time instead.
+fn start_of_day(date: Date<Local>) -> Result<DateTime<Local>, KimaiError> {
+    let naive = date.naive_local();
+    (0..24)
+        .find_map(|h| {
+            Local
+                .from_local_datetime(&naive.and_hms(h, 0, 0))
+                .earliest()
+        })
+        .ok_or_else(|| KimaiError::Other(format!("{} has no valid local time", naive)))
+}
+
+impl Period {
+    /// Get the begin and end of the period relative to `today`.
+    fn bounds(&self, today: Date<Local>) -> Result<(DateTime<Local>, DateTime<Local>), KimaiError> {
+        Ok(match self {
+            Period::ThisWeek => {
+                let monday =
+                    today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+                (
+                    start_of_day(monday)?,
+                    start_of_day(monday + chrono::Duration::days(7))?,
+                )
+            }
+            Period::ThisMonth => (
+                start_of_day(first_of_month(today.year(), today.month()))?,
+                start_of_day(today.succ())?,
+            ),
+            Period::LastMonth => (
+                start_of_day(first_of_month(today.year(), today.month() - 1))?,
+                start_of_day(first_of_month(today.year(), today.month()))?,
+            ),
+            Period::ThisQuarter => {
+                let quarter = (today.month() - 1) / 3 * 3 + 1;
+                (
+                    start_of_day(first_of_month(today.year(), quarter))?,
+                    start_of_day(first_of_month(today.year(), quarter + 3))?,
+                )
+            }
+            Period::LastQuarter => {
+                let quarter = (today.month() - 1) / 3 * 3 + 1;
+                (
+                    start_of_day(first_of_month(today.year() - 1, quarter + 9))?,
+                    start_of_day(first_of_month(today.year(), quarter))?,
+                )
+            }
+        })
+    }
+}
+
+/// Fetch the records of a timesheet and apply the client-side filters of the
+/// options to them. All pages are fetched if the records are summarized or
+/// filtered on the client side, since a single page would be incomplete.
+async fn get_filtered_timesheet(
+    client: &KimaiClient,
+    query: TimesheetQuery,
+    options: &TimesheetOptions,
+) -> Result<Vec<TimesheetRecord>, KimaiError> {
+    let page = match options.summarizes() || options.filters_client_side() {
+        true => None,
+        false => query.page,
+    };
+    let mut timesheet_records = client
+        .get_timesheet(TimesheetQuery { page, ..query })
+        .await?;
+    timesheet_records
+        .iter_mut()
+        .for_each(|r| r.canonicalize_tags(&client.config.tag_aliases));
+    let description_term = options.full_text_search.as_ref().map(|t| t.to_lowercase());
+    timesheet_records.retain(|r| {
+        !options.exclude_activities.contains(&r.activity)
+            && !options.exclude_projects.contains(&r.project)
+            && options.since_record.is_none_or(|id| r.id > id)
+            && description_term.as_ref().is_none_or(|t| {
+                r.description
+                    .as_ref()
+                    .is_some_and(|d| d.to_lowercase().contains(t))
+            })
+    });
+    Ok(timesheet_records)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn print_timesheet(
+    config_path: Option<String>,
+    profile: Option<String>,
+    user: Option<usize>,
+    mine: bool,
+    customers: Option<Vec<usize>>,
+    projects: Option<Vec<usize>>,
+    activities: Option<Vec<usize>>,
+    period: Option<Period>,
+    from: Option<String>,
+    to: Option<String>,
+    exported: Option<bool>,
+    options: TimesheetOptions,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let config = &client.config;
+    let (begin, end) = match period {
+        Some(p) => {
+            let (b, e) = p.bounds(Local::today())?;
+            (Some(b), Some(e))
+        }
+        None => (get_datetime_option(from)?, get_datetime_option(to)?),
+    };
+    let exported = match options.invoice_preview {
+        true => Some(false),
+        false => exported,
+    };
+    let user = resolve_user_filter(&client, user, mine).await?;
+    let mut timesheet_records = get_filtered_timesheet(
+        &client,
+        TimesheetQuery {
+            user: user.map(TimesheetUser::Id),
+            customers,
+            projects,
+            activities,
+            begin,
+            end,
+            exported,
+            billable: options.billable,
+            tags: options.tags.clone(),
+            term: options.full_text_search.clone(),
+            page: options.page,
+            size: options.page_size,
+        },
+        &options,
+    )
+    .await?;
+    if let Some(minutes) = options.round_up_to {
+        let increment = chrono::Duration::minutes(minutes as i64);
+        for record in timesheet_records.iter_mut() {
+            record.duration =
+                round_up_to_increment(chrono::Duration::seconds(record.duration), increment)
+                    .num_seconds();
+        }
+    }
+
+    let model = WorkingTimeModel {
+        hours_per_day: options
+            .working_hours
+            .unwrap_or(config.working_time_model.hours_per_day),
+        days_per_week: options
+            .working_days
+            .unwrap_or(config.working_time_model.days_per_week),
+        holidays: config.working_time_model.holidays.clone(),
+    };
+
+    let show_rates = options.invoice_preview || timesheet_records.iter().any(|r| r.rate != 0.0);
+    let personal_notes: BTreeMap<usize, PersonalNote> = read_state(NOTES_FILE)?;
+    let show_personal_notes = timesheet_records
+        .iter()
+        .any(|r| personal_notes.contains_key(&r.id));
+    let columns: Vec<Column> = match options.columns.clone().or(config.columns.clone()) {
+        Some(columns) => columns,
+        None => Column::ALL
+            .iter()
+            .filter(|c| match c {
+                Column::User => {
+                    options.show_user
+                        && !options.hidden_columns.contains(c)
+                        && !config.default_hidden_columns.contains(c)
+                }
+                Column::Hours => options.hours_per_record,
+                Column::Gap => options.show_gaps,
+                Column::Billable | Column::Exported => false,
+                Column::PersonalNote => show_personal_notes,
+                Column::Id => !options.hidden_columns.contains(c),
+                _ => {
+                    !options.hidden_columns.contains(c)
+                        && !config.default_hidden_columns.contains(c)
+                }
+            })
+            .filter(|c| show_rates || !matches!(c, Column::Rate | Column::Amount))
+            .copied()
+            .collect(),
+    };
+    let cell_format = CellFormat {
+        duration_format: options.duration_format,
+        currencies: match columns
+            .iter()
+            .any(|c| matches!(c, Column::Rate | Column::Amount))
+        {
+            true => client.get_project_currencies().await?,
+            false => HashMap::new(),
+        },
+        datetime_format: options
+            .datetime_format
+            .or_else(|| config.datetime_format.clone()),
+        project_colors: match options.output.is_table()
+            && !options.color_by_project
+            && columns.contains(&Column::Project)
+            && truecolor_supported()
+        {
+            true => client
+                .get_projects(None, None)
+                .await?
+                .into_iter()
+                .filter_map(|p| {
+                    let id = p.id;
+                    p.color.map(|c| (id, c))
+                })
+                .collect(),
+            false => HashMap::new(),
+        },
+        description_max_length: match options.output.is_table() {
+            true => options
+                .description_max_length
+                .unwrap_or(config.description_max_length),
+            false => 0,
+        },
+        borderless: options.output == OutputFormat::TableNoBorder,
+        color_by_project: options.color_by_project,
+        color_by_activity: options.color_by_activity,
+        relative_to: match options.human_duration {
+            true => Some(Local::now()),
+            false => None,
+        },
+        personal_notes,
+        gaps: match columns.contains(&Column::Gap) {
+            true => record_gaps(&timesheet_records, config.work_start_time, Local::now()),
+            false => HashMap::new(),
+        },
+        usernames: match columns.contains(&Column::User) {
+            true => client.get_usernames().await,
+            false => HashMap::new(),
+        },
+        project_names: HashMap::new(),
+        activity_names: HashMap::new(),
+    };
+
+    if options.invoice_preview {
+        let activities = client.get_activities(None, None).await?;
+        let mut currencies: Vec<&String> = timesheet_records
+            .iter()
+            .filter_map(|r| cell_format.currencies.get(&r.project))
+            .collect();
+        currencies.dedup();
+        let currency = match currencies.as_slice() {
+            [c] => Some(c.to_string()),
+            _ => None,
+        };
+        InvoicePreview::new(&timesheet_records, &activities, currency).print(options.output)?;
+    } else if options.total_only {
+        if !timesheet_records.is_empty() {
+            let total = timesheet_records.iter().map(|r| r.duration).sum();
+            println!("{}", format_duration(total, options.duration_format));
+        }
+    } else if options.agg_by_week {
+        print_weekly_aggregation(&timesheet_records, options.duration_format);
+    } else if options.agg_by_month {
+        print_monthly_aggregation(&timesheet_records, options.duration_format, &model);
+    } else if options.week_day_breakdown {
+        print_week_day_breakdown(&timesheet_records, options.duration_format, &model);
+    } else {
+        match options.output {
+            OutputFormat::Table | OutputFormat::TableNoBorder => {
+                print_timesheet_table(
+                    &timesheet_records,
+                    &columns,
+                    &cell_format,
+                    options.totals.unwrap_or(show_rates),
+                );
+                if options.show_day_bounds {
+                    if let Some((first, last)) = day_bounds(&timesheet_records, Local::now()) {
+                        let time_format = match first.date() == last.date() {
+                            true => TIME_FORMAT,
+                            false => cell_format.datetime_format(),
+                        };
+                        println!(
+                            "Work started at {}, ended at {}, total wall time {}",
+                            first.format(time_format),
+                            last.format(time_format),
+                            format_duration((last - first).num_seconds(), DurationFormat::Clock)
+                        );
+                    }
+                }
+                if let (Some(Period::ThisMonth | Period::LastMonth), Some(b), Some(e)) =
+                    (period, begin, end)
+                {
+                    println!();
+                    print_month_summary(
+                        &timesheet_records,
+                        b.naive_local().date(),
+                        e.naive_local().date(),
+                        options.duration_format,
+                        &model,
+                    );
+                }
+                if (options.project_summary || options.customer_summary)
+                    && !timesheet_records.is_empty()
+                {
+                    let (projects, _) = client
+                        .get_projects_and_activities_of(&timesheet_records)
+                        .await?;
+                    if options.project_summary {
+                        println!();
+                        print_project_summary(
+                            &timesheet_records,
+                            &projects,
+                            options.duration_format,
+                        );
+                    }
+                    if options.customer_summary {
+                        println!();
+                        let customers = client.get_customers(None).await?;
+                        print_customer_summary(
+                            &timesheet_records,
+                            &projects,
+                            &customers,
+                            options.duration_format,
+                        );
+                    }
+                }
+            }
+            OutputFormat::Json => print_json(&timesheet_records)?,
+            OutputFormat::Ndjson => print_ndjson(&timesheet_records)?,
+            OutputFormat::Csv | OutputFormat::Tsv => {
+                print_timesheet_csv(&timesheet_records, &columns, &cell_format, options.output)?
+            }
+            OutputFormat::ClockifyCsv => print_csv(
+                &clockify::COLUMNS,
+                &timesheet_clockify_rows(
+                    &client,
+                    &timesheet_records,
+                    options.user_email.as_deref().unwrap_or(""),
+                )
+                .await?,
+                OutputFormat::Csv,
+            )?,
+            OutputFormat::Markdown => print!(
+                "{}",
+                markdown::table(
+                    &columns.iter().map(|c| c.title()).collect::<Vec<&str>>(),
+                    &timesheet_rows(&timesheet_records, &columns, &cell_format),
+                    &columns
+                        .iter()
+                        .map(|c| c.right_aligned())
+                        .collect::<Vec<bool>>(),
+                )
+            ),
+            OutputFormat::Org => print!(
+                "{}",
+                org::table(
+                    &columns.iter().map(|c| c.title()).collect::<Vec<&str>>(),
+                    &timesheet_rows(&timesheet_records, &columns, &cell_format),
+                    &columns
+                        .iter()
+                        .map(|c| c.right_aligned())
+                        .collect::<Vec<bool>>(),
+                )
+            ),
+            OutputFormat::HarvestCsv => print_csv(
+                &harvest::COLUMNS,
+                &timesheet_harvest_rows(
+                    &client,
+                    &timesheet_records,
+                    options
+                        .date_format
+                        .as_deref()
+                        .unwrap_or(harvest::DEFAULT_DATE_FORMAT),
+                )
+                .await?,
+                OutputFormat::Csv,
+            )?,
+            OutputFormat::TogglCsv => print_csv(
+                &toggl::COLUMNS,
+                &timesheet_toggl_rows(
+                    &client,
+                    &timesheet_records,
+                    options.user_email.as_deref().unwrap_or(""),
+                )
+                .await?,
+                OutputFormat::Csv,
+            )?,
+            OutputFormat::Ical => {
+                let calendar = timesheet_calendar(&client, &timesheet_records).await?;
+                match options.out {
+                    Some(out) => {
+                        fs::write(&out, calendar)?;
+                        println!("Saved calendar to {}", out);
+                    }
+                    None => print!("{}", calendar),
+                }
+            }
+        }
+    }
+    if let (Some(minutes), true) = (options.round_up_to, options.output.is_table()) {
+        println!("(durations rounded up to nearest {}m)", minutes);
+    }
+    if options.overlap_check {
+        let pairs = overlapping_pairs(&timesheet_records, Local::now());
+        if !pairs.is_empty() {
+            eprintln!(
+                "⚠ Warning: {} overlapping record pairs detected: {}",
+                pairs.len(),
+                pairs
+                    .iter()
+                    .map(|(a, b)| format!("{} & {}", a, b))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Serialise records as iCalendar events, summarised by their project and
+/// activity.
+async fn timesheet_calendar(
+    client: &KimaiClient,
+    records: &[TimesheetRecord],
+) -> Result<String, KimaiError> {
+    let (projects, activities) = client.get_projects_and_activities_of(records).await?;
+    let now = Local::now();
+    let domain = client
+        .config
+        .host
+        .split("://")
+        .last()
+        .unwrap_or(&client.config.host);
+    let events: Vec<ical::Event> = records
+        .iter()
+        .map(|r| ical::Event {
+            uid: format!("timesheet-{}@{}", r.id, domain),
+            begin: r.begin,
+            end: r.end.unwrap_or(now),
+            summary: format!(
+                "{} / {}",
+                projects
+                    .iter()
+                    .find(|p| p.id == r.project)
+                    .map_or_else(|| r.project.to_string(), |p| p.name.clone()),
+                activities
+                    .iter()
+                    .find(|a| a.id == r.activity)
+                    .map_or_else(|| r.activity.to_string(), |a| a.name.clone()),
+            ),
+            description: r.description.clone(),
+        })
+        .collect();
+    Ok(ical::calendar(&events))
+}
+
+/// Get the rows of Clockify's import for the records, where activities
+/// become tasks and projects are prefixed with the name of their customer.
+/// Records still running end now.
+async fn timesheet_clockify_rows(
+    client: &KimaiClient,
+    records: &[TimesheetRecord],
+    email: &str,
+) -> Result<Vec<Vec<String>>, KimaiError> {
+    let (projects, activities) = client.get_projects_and_activities_of(records).await?;
+    let now = Local::now();
+    Ok(records
+        .iter()
+        .map(|r| {
+            clockify::row(&clockify::Entry {
+                description: r.description.clone().unwrap_or_default(),
+                email: email.to_string(),
+                project: projects.iter().find(|p| p.id == r.project).map_or_else(
+                    || r.project.to_string(),
+                    |p| format!("{} - {}", p.parent_title, p.name),
+                ),
+                task: activities
+                    .iter()
+                    .find(|a| a.id == r.activity)
+                    .map_or_else(|| r.activity.to_string(), |a| a.name.clone()),
+                tags: r.tags.clone(),
+                billable: r.rate != 0.0,
+                begin: r.begin,
+                end: r.end.unwrap_or(now),
+            })
+        })
+        .collect())
+}
+
+/// Get the rows of Toggl's detailed export for the records, where customers
+/// become clients and activities tasks. Records still running end now.
+async fn timesheet_toggl_rows(
+    client: &KimaiClient,
+    records: &[TimesheetRecord],
+    email: &str,
+) -> Result<Vec<Vec<String>>, KimaiError> {
+    let (projects, activities) = client.get_projects_and_activities_of(records).await?;
+    let usernames = client.get_usernames().await;
+    let now = Local::now();
+    Ok(records
+        .iter()
+        .map(|r| {
+            let project = projects.iter().find(|p| p.id == r.project);
+            toggl::row(&toggl::Entry {
+                user: usernames
+                    .get(&r.user)
+                    .cloned()
+                    .unwrap_or_else(|| r.user.to_string()),
+                email: email.to_string(),
+                client: project.map(|p| p.parent_title.clone()).unwrap_or_default(),
+                project: project.map_or_else(|| r.project.to_string(), |p| p.name.clone()),
+                task: activities
+                    .iter()
+                    .find(|a| a.id == r.activity)
+                    .map_or_else(|| r.activity.to_string(), |a| a.name.clone()),
+                description: r.description.clone().unwrap_or_default(),
+                billable: r.rate != 0.0,
+                begin: r.begin,
+                end: r.end.unwrap_or(now),
+                tags: r.tags.clone(),
+                amount: r.rate,
+            })
+        })
+        .collect())
+}
+
+/// Get the rows of Harvest's import for the records, where customers become
+/// clients and activities tasks.
+async fn timesheet_harvest_rows(
+    client: &KimaiClient,
+    records: &[TimesheetRecord],
+    date_format: &str,
+) -> Result<Vec<Vec<String>>, KimaiError> {
+    let (projects, activities) = client.get_projects_and_activities_of(records).await?;
+    Ok(records
+        .iter()
+        .map(|r| {
+            let project = projects.iter().find(|p| p.id == r.project);
+            harvest::row(
+                &harvest::Entry {
+                    date: r.begin.date(),
+                    client: project.map(|p| p.parent_title.clone()).unwrap_or_default(),
+                    project: project.map_or_else(|| r.project.to_string(), |p| p.name.clone()),
+                    task: activities
+                        .iter()
+                        .find(|a| a.id == r.activity)
+                        .map_or_else(|| r.activity.to_string(), |a| a.name.clone()),
+                    notes: r.description.clone().unwrap_or_default(),
+                    duration: r.duration,
+                },
+                date_format,
+            )
+        })
+        .collect())
+}
+
+/// Get the ID of the user to filter records by, which is the current user if
+/// `mine` is set.
+async fn resolve_user_filter(
+    client: &KimaiClient,
+    user: Option<usize>,
+    mine: bool,
+) -> Result<Option<usize>, KimaiError> {
+    match mine {
+        true => Ok(Some(client.get_current_user().await?.id)),
+        false => Ok(user),
+    }
+}
+
+/// Format the difference between two durations with an arrow showing its
+/// direction, like `▲ +2h30`.
+fn format_delta(seconds: i64) -> String {
+    match seconds.cmp(&0) {
+        std::cmp::Ordering::Greater => format!("▲ +{}", format_hours(seconds)),
+        std::cmp::Ordering::Less => format!("▼ -{}", format_hours(-seconds)),
+        std::cmp::Ordering::Equal => format_hours(0),
+    }
+}
+
+/// Print the hours tracked this month per project, optionally side by side
+/// with the previous month.
+pub async fn print_monthly_report(
+    config_path: Option<String>,
+    profile: Option<String>,
+    compare_previous: bool,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let today = Local::today();
+    let (begin, end) = Period::ThisMonth.bounds(today)?;
+    let (previous_begin, previous_end) = Period::LastMonth.bounds(today)?;
+
+    let current = client.get_timesheet(TimesheetQuery {
+        begin: Some(begin),
+        end: Some(end),
+        ..TimesheetQuery::default()
+    });
+    let (records, previous_records) = match compare_previous {
+        true => tokio::try_join!(
+            current,
+            client.get_timesheet(TimesheetQuery {
+                begin: Some(previous_begin),
+                end: Some(previous_end),
+                ..TimesheetQuery::default()
+            })
+        )?,
+        false => (current.await?, Vec::new()),
+    };
+    let projects = client.get_projects(None, None).await?;
+
+    let mut totals: Vec<(usize, i64)> = project_totals(&records)
+        .into_iter()
+        .map(|(project, _, duration)| (project, duration))
+        .collect();
+    let previous_totals: BTreeMap<usize, i64> = project_totals(&previous_records)
+        .into_iter()
+        .map(|(project, _, duration)| (project, duration))
+        .collect();
+    for project in previous_totals.keys() {
+        if !totals.iter().any(|(p, _)| p == project) {
+            totals.push((*project, 0));
+        }
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    let mut titles = row!["Project ID", "Name", begin.format("%Y-%m")];
+    if compare_previous {
+        titles.add_cell(Cell::new(&previous_begin.format("%Y-%m").to_string()));
+        titles.add_cell(Cell::new("Delta"));
+    }
+    table.set_titles(titles);
+    for (id, duration) in totals {
+        let name = projects
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.name.as_str())
+            .unwrap_or("");
+        let mut row = row![r->id, name, r->format_hours(duration)];
+        if compare_previous {
+            let previous = previous_totals.get(&id).copied().unwrap_or(0);
+            row.add_cell(Cell::new(&format_hours(previous)).style_spec("r"));
+            row.add_cell(Cell::new(&format_delta(duration - previous)).style_spec("r"));
+        }
+        table.add_row(row);
+    }
+    table.printstd();
+
+    Ok(())
+}
+
+/// Parse a datetime, a time of today, or a relative duration like `1h30m`,
+/// meaning that long ago.
+pub fn str_to_datetime(date_str: &str) -> Result<DateTime<Local>, KimaiError> {
+    match NaiveDateTime::parse_from_str(date_str, DATETIME_FORMAT) {
+        Ok(d) => Local.from_local_datetime(&d).earliest().ok_or_else(|| {
+            KimaiError::Other(format!("{} doesn't exist in the local time zone", date_str))
+        }),
+        Err(_) => match NaiveTime::parse_from_str(date_str, TIME_FORMAT) {
+            Ok(t) => Local::today()
+                .and_time(t)
+                .ok_or_else(|| KimaiError::Other(format!("{} doesn't exist today", date_str))),
+            Err(e) => match DateTime::parse_from_str(date_str, OFFSET_DATETIME_FORMAT)
+                .or_else(|_| DateTime::parse_from_rfc3339(date_str))
+            {
+                Ok(d) => Ok(d.with_timezone(&Local)),
+                Err(_) => match parse_duration_offset(date_str) {
+                    Some(d) => Local::now().checked_sub_signed(d).ok_or_else(|| {
+                        KimaiError::DurationParse(format!("\"{}\" ago is out of range", date_str))
+                    }),
+                    None => Err(KimaiError::from(e)),
+                },
+            },
+        },
+    }
+}
+
+/// Parse how long ago something happened, given in hours and minutes like
+/// `1h30m`, `1h`, `1h30` or `90m`. Signs and other units are not accepted.
+pub fn parse_duration_offset(duration_str: &str) -> Option<chrono::Duration> {
+    fn number(digits: &str) -> Option<i64> {
+        match !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+            true => digits.parse().ok(),
+            false => None,
+        }
+    }
+
+    let (hours, minutes) = match duration_str.trim().split_once('h') {
+        Some((h, m)) => {
+            let m = m.strip_suffix('m').unwrap_or(m);
+            (number(h)?, if m.is_empty() { 0 } else { number(m)? })
+        }
+        None => (0, number(duration_str.trim().strip_suffix('m')?)?),
+    };
+    let seconds = hours
+        .checked_mul(60)
+        .and_then(|m| m.checked_add(minutes))
+        .and_then(|m| m.checked_mul(60))
+        .filter(|s| *s <= i64::MAX / 1000)?;
+
+    Some(chrono::Duration::seconds(seconds))
+}
+
+/// Parse a relative duration like `1h30m`, `90m` or `-10m`.
+///
+/// Supported units are `d`, `h`, `m` and `s`. A leading `-` makes the
+/// duration negative, a leading `+` is accepted as well.
+pub fn parse_relative_duration(duration_str: &str) -> Result<chrono::Duration, KimaiError> {
+    let parse_error =
+        || KimaiError::DurationParse(format!("invalid duration \"{}\"", duration_str));
+    let trimmed = duration_str.trim();
+    let (negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    if unsigned.is_empty() {
+        return Err(parse_error());
+    }
+
+    let mut seconds: i64 = 0;
+    let mut number = String::new();
+    for c in unsigned.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let value = number.parse::<i64>().map_err(|_| parse_error())?;
+        let unit = match c {
+            'd' => 24 * 60 * 60,
+            'h' => 60 * 60,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(parse_error()),
+        };
+        seconds = value
+            .checked_mul(unit)
+            .and_then(|v| seconds.checked_add(v))
+            .ok_or_else(parse_error)?;
+        number.clear();
+    }
+    // `chrono::Duration::seconds` panics beyond its range of milliseconds
+    if !number.is_empty() || seconds > i64::MAX / 1000 {
+        return Err(parse_error());
+    }
+
+    let duration = chrono::Duration::seconds(seconds);
+    Ok(if negative { -duration } else { duration })
+}
+
+/// Parse a duration given like a clock time, `HH:MM`, e.g. `2:30`.
+pub fn parse_clock_duration(duration_str: &str) -> Result<chrono::Duration, KimaiError> {
+    let parse_error =
+        || KimaiError::DurationParse(format!("invalid duration \"{}\"", duration_str));
+    let (hours, minutes) = duration_str
+        .trim()
+        .split_once(':')
+        .ok_or_else(parse_error)?;
+    let hours = hours.parse::<u32>().map_err(|_| parse_error())?;
+    let minutes = match minutes.parse::<u32>() {
+        Ok(m) if m < 60 && minutes.len() == 2 => m,
+        _ => return Err(parse_error()),
+    };
+    Ok(chrono::Duration::hours(hours as i64) + chrono::Duration::minutes(minutes as i64))
+}
+
+fn intervals_overlap(
+    a_begin: DateTime<Local>,
+    a_end: DateTime<Local>,
+    b_begin: DateTime<Local>,
+    b_end: DateTime<Local>,
+) -> bool {
+    a_begin < b_end && b_begin < a_end
+}
+
+/// Get the IDs of all pairs of overlapping records, treating open ends as
+/// `now`.
+fn overlapping_pairs(records: &[TimesheetRecord], now: DateTime<Local>) -> Vec<(usize, usize)> {
+    let mut sorted: Vec<&TimesheetRecord> = records.iter().collect();
+    sorted.sort_by_key(|r| r.begin);
+    let mut pairs = Vec::new();
+    for (i, a) in sorted.iter().enumerate() {
+        let a_end = a.end.unwrap_or(now);
+        for b in sorted[i + 1..].iter().take_while(|b| b.begin < a_end) {
+            if intervals_overlap(a.begin, a_end, b.begin, b.end.unwrap_or(now)) {
+                pairs.push((a.id, b.id));
+            }
+        }
+    }
+    pairs
+}
+
+/// Get the seconds between the begin of each record and the end of the
+/// records before it on the same day, by record ID, treating open ends as
+/// `now`. For the first record of a day, the gap is counted from
+/// `work_start`, or from midnight if not given. Overlapping records have a
+/// gap of zero.
+fn record_gaps(
+    records: &[TimesheetRecord],
+    work_start: Option<NaiveTime>,
+    now: DateTime<Local>,
+) -> HashMap<usize, i64> {
+    let mut sorted: Vec<&TimesheetRecord> = records.iter().collect();
+    sorted.sort_by_key(|r| r.begin);
+    let mut gaps = HashMap::new();
+    let mut latest_end: Option<DateTime<Local>> = None;
+    for record in sorted {
+        let since = match latest_end {
+            Some(e) if e.date() == record.begin.date() => e,
+            _ => Local
+                .from_local_datetime(
+                    &record
+                        .begin
+                        .naive_local()
+                        .date()
+                        .and_time(work_start.unwrap_or_else(|| NaiveTime::from_hms(0, 0, 0))),
+                )
+                .earliest()
+                .unwrap_or(record.begin),
+        };
+        gaps.insert(record.id, (record.begin - since).num_seconds().max(0));
+        let end = record.end.unwrap_or(now);
+        latest_end = Some(latest_end.map_or(end, |e| e.max(end)));
+    }
+    gaps
+}
+
+/// Check whether an existing record and a new one overlap. Open ends are
+/// treated as the current time.
+fn records_overlap(a: &TimesheetRecord, b: &NewTimesheetRecord) -> bool {
+    let now = Local::now();
+    let b_begin = match Local.from_local_datetime(&b.begin).earliest() {
+        Some(b) => b,
+        None => return false,
+    };
+    let b_end = match b.end.map(|e| Local.from_local_datetime(&e).earliest()) {
+        Some(Some(e)) => e,
+        Some(None) => return false,
+        None => now,
+    };
+    intervals_overlap(a.begin, a.end.unwrap_or(now), b_begin, b_end)
+}
+
+/// Get the given datetime, today's `start_of_day` if given instead, or the
+/// current time otherwise.
+fn get_datetime(
+    datetime_str: Option<String>,
+    start_of_day: Option<NaiveTime>,
+) -> Result<DateTime<Local>, KimaiError> {
+    match (datetime_str, start_of_day) {
+        (Some(s), _) => str_to_datetime(&s),
+        (None, Some(t)) => Local::today()
+            .and_time(t)
+            .ok_or_else(|| KimaiError::Other("Invalid start of day!".to_string())),
+        (None, None) => {
+            let mut now = Local::now();
+            now = now - chrono::Duration::nanoseconds(now.timestamp_subsec_nanos() as i64);
+            Ok(now)
+        }
+    }
+}
+
+fn get_datetime_option(
+    datetime_str: Option<String>,
+) -> Result<Option<DateTime<Local>>, KimaiError> {
+    match datetime_str {
+        Some(s) => Ok(Some(str_to_datetime(&s)?)),
+        None => Ok(None),
+    }
+}
+
+/// Maximum length of a description taken from a commit message
+const COMMIT_DESCRIPTION_LENGTH: usize = 255;
+
+/// Shorten a string to at most `max` characters, marking the cut with `…`.
+fn truncate_with_ellipsis(s: &str, max: usize) -> String {
+    match s.chars().count() > max {
+        true => {
+            let mut truncated: String = s.chars().take(max.saturating_sub(1)).collect();
+            truncated.push('…');
+            truncated
+        }
+        false => s.to_string(),
+    }
+}
+
+/// Get the subject of the last commit in the current git repository to be
+/// used as a description. Prints a warning and returns `None` if that fails.
+pub fn description_from_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["log", "-1", "--pretty=%s"])
+        .output();
+    match output {
+        Ok(o) if o.status.success() => {
+            let subject = String::from_utf8_lossy(&o.stdout).trim().to_string();
+            Some(truncate_with_ellipsis(&subject, COMMIT_DESCRIPTION_LENGTH))
+        }
+        _ => {
+            eprintln!("Warning: No git commit found, using no description.");
+            None
+        }
+    }
+}
+
+/// Name of the custom field the git remote URL is stored in
+const GIT_REMOTE_META_FIELD: &str = "git_remote";
+
+/// Get the URL of the origin remote of the current git repository. Prints a
+/// warning and returns `None` if that fails.
+fn git_remote_url() -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "--get", "remote.origin.url"])
+        .output();
+    match output {
+        Ok(o) if o.status.success() => Some(String::from_utf8_lossy(&o.stdout).trim().to_string()),
+        _ => {
+            eprintln!("Warning: No git remote found, not recording it.");
+            None
+        }
+    }
+}
+
+/// Ask the user a yes/no question on the command line. If `no_prompt` is
+/// set, the question is answered with no without asking.
+fn confirm(question: &str, no_prompt: bool) -> Result<bool, KimaiError> {
+    if no_prompt {
+        println!("{} [y/N] n", question);
+        return Ok(false);
+    }
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Ask the user for the value of every variable of the template and fill them
+/// in.
+fn fill_in_template(template: &Template) -> Result<Template, KimaiError> {
+    let mut values = HashMap::new();
+    for name in template.variables() {
+        print!("{}: ", name);
+        io::stdout().flush()?;
+        let mut value = String::new();
+        io::stdin().read_line(&mut value)?;
+        values.insert(name, value.trim().to_string());
+    }
+    Ok(template.render(&values))
+}
+
+/// Let the user pick one of the recently used projects and activities by its
+/// number.
+async fn select_recent_project(
+    client: &KimaiClient,
+    recent: &[RecentProject],
+) -> Result<RecentProject, KimaiError> {
+    if recent.is_empty() {
+        return Err(KimaiError::Other(
+            "No project given and no recently used ones!".to_string(),
+        ));
+    }
+    let projects = client.get_projects(None, None).await?;
+    let activities = client.get_activities(None, None).await?;
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["#", "Project", "Activity"]);
+    for (i, r) in recent.iter().enumerate() {
+        table.add_row(row![
+            r->i + 1,
+            projects
+                .iter()
+                .find(|p| p.id == r.project)
+                .map_or_else(|| r.project.to_string(), |p| p.name.clone()),
+            activities
+                .iter()
+                .find(|a| a.id == r.activity)
+                .map_or_else(|| r.activity.to_string(), |a| a.name.clone()),
+        ]);
+    }
+    table.printstd();
+
+    print!("Select a project [1-{}]: ", recent.len());
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    answer
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| recent.get(i))
+        .copied()
+        .ok_or_else(|| KimaiError::Other(format!("Invalid selection \"{}\"!", answer.trim())))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn print_begin_timesheet_record(
+    config_path: Option<String>,
+    profile: Option<String>,
+    user: Option<usize>,
+    project: Option<usize>,
+    activity: Option<usize>,
+    begin: Option<String>,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+    no_prompt: bool,
+    check_running: bool,
+    force: bool,
+    duration_budget: bool,
+    warn_at: Option<f64>,
+    critical_at: Option<f64>,
+    start_of_day: bool,
+    estimate: Option<String>,
+    git_remote: bool,
+    remind: Option<String>,
+    personal_note: Option<String>,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let config = &client.config;
+    let budget_thresholds = BudgetThresholds::new(
+        warn_at.unwrap_or(config.budget_thresholds.warning),
+        critical_at.unwrap_or(config.budget_thresholds.critical),
+    )?;
+    let estimate = match estimate {
+        Some(e) => Some(parse_relative_duration(&e)?),
+        None => None,
+    };
+    let remind = match remind {
+        Some(r) => Some(parse_relative_duration(&r)?),
+        None => None,
+    };
+    let (project, activity) = match project {
+        Some(p) => (
+            p,
+            activity.ok_or_else(|| KimaiError::Other("No activity given!".to_string()))?,
+        ),
+        None if no_prompt => {
+            return Err(KimaiError::Other("No project given!".to_string()));
+        }
+        None => {
+            let recent: Vec<RecentProject> = read_state(RECENT_PROJECTS_FILE)?;
+            let selected = select_recent_project(&client, &recent).await?;
+            (selected.project, activity.unwrap_or(selected.activity))
+        }
+    };
+
+    let active_records = match check_running || !force {
+        true => client.get_active_timesheet().await?,
+        false => Vec::new(),
+    };
+    let over_limit = !force && active_records.len() >= config.max_active_timers;
+    if over_limit || (check_running && !active_records.is_empty()) {
+        print_timesheet_entities(
+            &active_records,
+            &[],
+            &[],
+            None,
+            false,
+            config.datetime_format.as_deref(),
+        );
+        // With --check-running, confirming starts the record even over the limit
+        if !check_running {
+            return Err(KimaiError::Other(format!(
+                "At most {} timers may run at the same time, use --force to start anyway!",
+                config.max_active_timers
+            )));
+        }
+        if !confirm("A timer is already running — start anyway?", no_prompt)? {
+            return Err(KimaiError::Other("A timer is already running!".to_string()));
+        }
+    }
+
+    let record = client
+        .begin_timesheet_record(
+            match user {
+                Some(u) => u,
+                None => client.get_current_user().await?.id,
+            },
+            project,
+            activity,
+            get_datetime(
+                begin,
+                match start_of_day {
+                    true => Some(config.start_of_day),
+                    false => None,
+                },
+            )?,
+            description,
+            tags,
+            match git_remote {
+                true => git_remote_url()
+                    .map(|u| (GIT_REMOTE_META_FIELD.to_string(), u))
+                    .into_iter()
+                    .collect(),
+                false => HashMap::new(),
+            },
+        )
+        .await?;
+
+    println!("Started new timesheet record:");
+    record.print_table(&client).await;
+
+    // An unreadable list of recent projects is started over
+    let mut recent: Vec<RecentProject> = read_state(RECENT_PROJECTS_FILE).unwrap_or_default();
+    remember_recent_project(&mut recent, RecentProject { project, activity });
+    if let Err(e) = write_state(RECENT_PROJECTS_FILE, &recent) {
+        eprintln!("Warning: Could not remember the recent project: {}", e);
+    }
+
+    if let Some(n) = personal_note {
+        set_personal_note(record.id, &n)?;
+    }
+
+    if let Some(e) = estimate {
+        let mut estimates: Vec<Estimate> = read_state(ESTIMATES_FILE)?;
+        estimates.push(Estimate {
+            record_id: record.id,
+            estimate_seconds: e.num_seconds(),
+        });
+        write_state(ESTIMATES_FILE, &estimates)?;
+    }
+
+    if let Some(r) = remind {
+        match schedule_reminder(r) {
+            Ok(()) => println!("Reminding you in {}.", format_hours(r.num_seconds())),
+            Err(e) => eprintln!("Warning: Could not schedule a reminder: {}", e),
+        }
+    }
+
+    if duration_budget {
+        print_project_time_budget(&client, project, budget_thresholds).await?;
+    }
+
+    Ok(())
+}
+
+const REMINDER_TITLE: &str = "Kimai";
+const REMINDER_MESSAGE: &str = "Timer reminder";
+
+/// Schedule a desktop notification after the given duration, without keeping
+/// this process running. Uses `at` with `notify-send`, and on macOS a detached
+/// `osascript` notification.
+fn schedule_reminder(after: chrono::Duration) -> Result<(), KimaiError> {
+    if cfg!(target_os = "macos") {
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!(
+                "sleep {} && osascript -e 'display notification \"{}\" with title \"{}\"'",
+                after.num_seconds().max(0),
+                REMINDER_MESSAGE,
+                REMINDER_TITLE
+            ))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        return Ok(());
+    }
+
+    let minutes = (after.num_seconds() + 59).div_euclid(60).max(1);
+    let mut at = Command::new("at")
+        .arg(format!("now + {} minutes", minutes))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(stdin) = at.stdin.as_mut() {
+        writeln!(
+            stdin,
+            "notify-send '{}' '{}'",
+            REMINDER_TITLE, REMINDER_MESSAGE
+        )?;
+    }
+    match at.wait()?.success() {
+        true => Ok(()),
+        false => Err(KimaiError::Other(
+            "at failed to schedule the job".to_string(),
+        )),
+    }
+}
+
+/// Shares of a project's time budget above which its use is highlighted
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BudgetThresholds {
+    warning: f64,
+    critical: f64,
+}
+
+impl Default for BudgetThresholds {
+    fn default() -> Self {
+        BudgetThresholds {
+            warning: 0.7,
+            critical: 0.9,
+        }
+    }
+}
+
+impl BudgetThresholds {
+    fn new(warning: f64, critical: f64) -> Result<Self, KimaiError> {
+        if !(0.0..=1.0).contains(&warning) || !(0.0..=1.0).contains(&critical) {
+            return Err(KimaiError::Other(
+                "Thresholds must be between 0 and 1!".to_string(),
+            ));
+        }
+        if warning > critical {
+            return Err(KimaiError::Other(
+                "The warning threshold must not be above the critical one!".to_string(),
+            ));
+        }
+        Ok(BudgetThresholds { warning, critical })
+    }
+
+    /// Get the highest threshold exceeded by the share and its color.
+    fn exceeded(&self, share: f64) -> Option<(f64, colored::Color)> {
+        if share > self.critical {
+            Some((self.critical, colored::Color::Red))
+        } else if share > self.warning {
+            Some((self.warning, colored::Color::Yellow))
+        } else {
+            None
+        }
+    }
+}
+
+fn format_hours(seconds: i64) -> String {
+    let d = chrono::Duration::seconds(seconds);
+    format!("{}h{:02}", d.num_hours(), d.num_minutes() % 60)
+}
+
+async fn print_project_time_budget(
+    client: &KimaiClient,
+    id: usize,
+    thresholds: BudgetThresholds,
+) -> Result<(), KimaiError> {
+    let project = client.get_project(id).await?;
+    let total: i64 = client
+        .get_timesheet(TimesheetQuery {
+            user: Some(TimesheetUser::All),
+            projects: Some(vec![id]),
+            ..TimesheetQuery::default()
+        })
+        .await?
+        .iter()
+        .map(|r| r.duration)
+        .sum();
+
+    let time_budget = match project.time_budget() {
+        Some(t) => t,
+        None => {
+            println!("Project total: {} (no time budget)", format_hours(total));
+            return Ok(());
+        }
+    };
+
+    let share = total as f64 / time_budget as f64;
+    let percentage = format!("{:.1}%", share * 100.0);
+    println!(
+        "Project total: {} / {} budget ({})",
+        format_hours(total),
+        format_hours(time_budget as i64),
+        match thresholds.exceeded(share) {
+            Some((_, color)) => percentage.color(color).to_string(),
+            None => percentage,
+        }
+    );
+    if let Some((threshold, color)) = thresholds.exceeded(share) {
+        println!(
+            "{}",
+            format!(
+                "Warning: project \"{}\" used more than {:.0}% of its time budget!",
+                project.name,
+                threshold * 100.0
+            )
+            .color(color)
+        );
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn print_log_timesheet_record(
+    config_path: Option<String>,
+    profile: Option<String>,
+    user: Option<usize>,
+    project: Option<usize>,
+    activity: Option<usize>,
+    begin: String,
+    end: Option<String>,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+    force: bool,
+    no_prompt: bool,
+    billing_increment: Option<u64>,
+    reuse_last: bool,
+    from_jira: Option<String>,
+    template_file: Option<String>,
+    personal_note: Option<String>,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let config = &client.config;
+    let (project, activity, description, tags) = match template_file {
+        Some(path) => {
+            let template = fill_in_template(&toml::from_str(&fs::read_to_string(path)?)?)?;
+            (
+                project.or(template.project),
+                activity.or(template.activity),
+                description.or(template.description),
+                tags.or(match template.tags.is_empty() {
+                    true => None,
+                    false => Some(template.tags),
+                }),
+            )
+        }
+        None => (project, activity, description, tags),
+    };
+    let description = match (description, from_jira) {
+        (None, Some(ticket)) => {
+            let jira_config = config
+                .jira
+                .as_ref()
+                .ok_or_else(|| KimaiError::Config("No [jira] section in config!".to_string()))?;
+            Some(jira::get_issue_summary(jira_config, &ticket).await?)
+        }
+        (description, _) => description,
+    };
+    let (project, activity, description, tags) = match reuse_last {
+        true => {
+            let last = client
+                .get_last_timesheet_record()
+                .await?
+                .ok_or_else(|| KimaiError::Other("No record to reuse found!".to_string()))?;
+            let project = project.unwrap_or(last.project);
+            let activity = activity.unwrap_or(last.activity);
+            println!(
+                "Reusing: {} / {}",
+                client.get_project(project).await?.name,
+                client.get_activity(activity).await?.name
+            );
+            (
+                project,
+                activity,
+                description.or(last.description),
+                tags.or(match last.tags.is_empty() {
+                    true => None,
+                    false => Some(last.tags),
+                }),
+            )
+        }
+        false => (
+            project.ok_or_else(|| KimaiError::Other("No project given!".to_string()))?,
+            activity.ok_or_else(|| KimaiError::Other("No activity given!".to_string()))?,
+            description,
+            tags,
+        ),
+    };
+    let begin = str_to_datetime(&begin)?;
+    let mut end = get_datetime_option(end)?;
+
+    if let (Some(e), Some(increment)) =
+        (end, billing_increment.or(config.billing_increment_minutes))
+    {
+        let duration = e - begin;
+        let rounded = round_to_increment(duration, chrono::Duration::minutes(increment as i64));
+        if rounded != duration {
+            println!(
+                "Rounded from {} to {}",
+                format_duration(duration.num_seconds(), DurationFormat::Clock),
+                format_duration(rounded.num_seconds(), DurationFormat::Clock)
+            );
+            end = Some(begin + rounded);
+        }
+    }
+
+    if !force {
+        let new_record = NewTimesheetRecord {
+            project,
+            activity,
+            begin: begin.naive_local(),
+            end: end.map(|e| e.naive_local()),
+            description: None,
+            tags: None,
+            meta_fields: HashMap::new(),
+        };
+        let overlapping: Vec<TimesheetRecord> = client
+            .get_timesheet_around(begin, end.unwrap_or_else(Local::now))
+            .await?
+            .into_iter()
+            .filter(|r| records_overlap(r, &new_record))
+            .collect();
+        if !overlapping.is_empty() {
+            println!("The new record would overlap with:");
+            print_timesheets(&overlapping);
+            if !confirm("Create anyway?", no_prompt)? {
+                return Err(KimaiError::Other(
+                    "New record overlaps with existing records!".to_string(),
+                ));
+            }
+        }
+    }
+
+    let record = client
+        .log_timesheet_record(
+            match user {
+                Some(u) => u,
+                None => client.get_current_user().await?.id,
+            },
+            project,
+            activity,
+            begin,
+            end,
+            description,
+            tags,
+        )
+        .await?;
+
+    println!("Logged new timesheet record:");
+    record.print_table(&client).await;
+
+    if let Some(n) = personal_note {
+        set_personal_note(record.id, &n)?;
+    }
+
+    Ok(())
+}
+
+/// Round a duration to the nearest multiple of `increment`, rounding halfway
+/// cases up. A positive duration is rounded to at least one increment, so
+/// no record becomes empty.
+fn round_to_increment(duration: chrono::Duration, increment: chrono::Duration) -> chrono::Duration {
+    let increment = increment.num_seconds();
+    if increment <= 0 {
+        return duration;
+    }
+    let seconds = duration.num_seconds();
+    let rounded = (seconds + increment / 2).div_euclid(increment) * increment;
+    match rounded == 0 && seconds > 0 {
+        true => chrono::Duration::seconds(increment),
+        false => chrono::Duration::seconds(rounded),
+    }
+}
+
+/// Round a duration up to the next multiple of `increment`.
+fn round_up_to_increment(
+    duration: chrono::Duration,
+    increment: chrono::Duration,
+) -> chrono::Duration {
+    let increment = increment.num_seconds();
+    if increment <= 0 {
+        return duration;
+    }
+    let seconds = duration.num_seconds();
+    chrono::Duration::seconds((seconds + increment - 1).div_euclid(increment) * increment)
+}
+
+/// A timesheet record as read from a CSV file or YAML documents for importing
+#[derive(Debug, Default, PartialEq, Deserialize)]
+struct ImportTimesheetRecord {
+    begin: String,
+    end: Option<String>,
+    project: usize,
+    activity: usize,
+    description: Option<String>,
+    tags: Option<String>,
+}
+
+/// Log all timesheet records from a CSV file.
+///
+/// The file needs a header row with the columns `begin`, `end`, `project`,
+/// `activity`, `description` and `tags`, where multiple tags are separated by
+/// commas. Records failing to be logged are reported, but don't stop the
+/// import.
+pub async fn print_import_timesheet_records(
+    config_path: Option<String>,
+    profile: Option<String>,
+    csv_path: String,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let user = client.get_current_user().await?.id;
+
+    let records = csv::Reader::from_path(&csv_path)?
+        .deserialize()
+        .collect::<Result<Vec<ImportTimesheetRecord>, csv::Error>>()?;
+
+    import_timesheet_records(&client, user, &records).await
+}
+
+/// A timesheet record as read from a YAML document, where tags are either a
+/// single string or a sequence of strings.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct YamlTimesheetRecord {
+    begin: String,
+    end: Option<String>,
+    project: usize,
+    activity: usize,
+    description: Option<String>,
+    tags: Option<YamlTags>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum YamlTags {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Parse a sequence of YAML documents, separated by `---`, into records to
+/// import. Empty documents are skipped.
+fn parse_yaml_timesheet_records(input: &str) -> Result<Vec<ImportTimesheetRecord>, KimaiError> {
+    let mut records = Vec::new();
+    for (i, document) in serde_yaml::Deserializer::from_str(input).enumerate() {
+        let record = Option::<YamlTimesheetRecord>::deserialize(document)
+            .map_err(|e| KimaiError::Yaml(format!("document {}: {}", i + 1, e)))?;
+        if let Some(record) = record {
+            let tags = match record.tags {
+                Some(YamlTags::One(tag)) => vec![tag],
+                Some(YamlTags::Many(tags)) => tags,
+                None => Vec::new(),
+            };
+            records.push(ImportTimesheetRecord {
+                begin: record.begin,
+                end: record.end,
+                project: record.project,
+                activity: record.activity,
+                description: record.description,
+                tags: match tags.is_empty() {
+                    true => None,
+                    false => Some(tags.join(",")),
+                },
+            });
+        }
+    }
+
+    Ok(records)
+}
+
+/// Log all timesheet records from YAML documents read from stdin, as parsed
+/// by `parse_yaml_timesheet_records`.
+pub async fn print_import_yaml_timesheet_records(
+    config_path: Option<String>,
+    profile: Option<String>,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let records = parse_yaml_timesheet_records(&input)?;
+    let user = client.get_current_user().await?.id;
+
+    import_timesheet_records(&client, user, &records).await
+}
+
+/// Log the given records, reporting the ones failing to be logged without
+/// stopping.
+async fn import_timesheet_records(
+    client: &KimaiClient,
+    user: usize,
+    records: &[ImportTimesheetRecord],
+) -> Result<(), KimaiError> {
+    let progress_bar = ProgressBar::new(records.len() as u64);
+    progress_bar.set_style(
+        ProgressStyle::with_template("{msg} [{bar:40}] {pos}/{len} (ETA {eta})")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+
+    let mut failed = Vec::new();
+    for (i, record) in records.iter().enumerate() {
+        progress_bar.set_message(format!("Creating record {} of {}...", i + 1, records.len()));
+        let result = match (
+            str_to_datetime(&record.begin),
+            get_datetime_option(record.end.clone()),
+        ) {
+            (Ok(begin), Ok(end)) => {
+                client
+                    .log_timesheet_record(
+                        user,
+                        record.project,
+                        record.activity,
+                        begin,
+                        end,
+                        record.description.clone(),
+                        record
+                            .tags
+                            .as_ref()
+                            .map(|t| t.split(',').map(|s| s.trim().to_string()).collect()),
+                    )
+                    .await
+            }
+            (Err(e), _) | (_, Err(e)) => Err(e),
+        };
+        if let Err(e) = result {
+            progress_bar.println(format!("Record {} failed: {}", i + 1, e));
+            failed.push((i + 1, record));
+        }
+        progress_bar.inc(1);
+    }
+    progress_bar.finish_and_clear();
+
+    println!(
+        "Created {}/{} records; {} failed",
+        records.len() - failed.len(),
+        records.len(),
+        failed.len()
+    );
+    if !failed.is_empty() {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+        table.set_titles(row![
+            "Row",
+            "Begin",
+            "End",
+            "Project",
+            "Activity",
+            "Description"
+        ]);
+        for (row, record) in failed {
+            table.add_row(row![
+                r->row,
+                record.begin,
+                record.end.as_deref().unwrap_or(""),
+                r->record.project,
+                r->record.activity,
+                record.description.as_deref().unwrap_or(""),
+            ]);
+        }
+        table.printstd();
+    }
+
+    Ok(())
+}
+
+pub async fn print_end_timesheet_record(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+    at: Option<String>,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let record = match at {
+        Some(a) => {
+            let end = str_to_datetime(&a)?;
+            if end > Local::now() {
+                return Err(KimaiError::Other(format!("{} is in the future!", a)));
+            }
+            if end <= client.get_timesheet_record(id).await?.begin {
+                return Err(KimaiError::Other(format!(
+                    "{} is not after the begin of the record!",
+                    a
+                )));
+            }
+            client
+                .patch_timesheet_record(id, None, None, None, Some(end), None, None)
+                .await?
+        }
+        None => client.end_timesheet_record(id).await?,
+    };
+    println!("Ended timesheet record:");
+    record.print_table(&client).await;
+
+    let mut estimates: Vec<Estimate> = read_state(ESTIMATES_FILE)?;
+    if let Some(e) = find_estimate(&estimates, id) {
+        println!("{}", estimate_outcome(record.duration, e));
+        estimates.retain(|e| e.record_id != id);
+        write_state(ESTIMATES_FILE, &estimates)?;
+    }
+
+    Ok(())
+}
+
+pub async fn print_restart_timesheet_record(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let record = client.restart_timesheet_record(id).await?;
+    println!("Restarted timesheet record:");
+    record.print_table(&client).await;
+
+    Ok(())
+}
+
+/// Delete a timesheet record after showing it and asking for confirmation,
+/// unless `force` is set.
+pub async fn print_delete_timesheet_record(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+    force: bool,
+    no_prompt: bool,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    if !force {
+        client
+            .get_timesheet_record(id)
+            .await?
+            .print_table(&client)
+            .await;
+        if !confirm("Delete this record?", no_prompt)? {
+            println!("Not deleting timesheet record {}.", id);
+            return Ok(());
+        }
+    }
+    client.delete_timesheet_record(id).await?;
+    println!("Deleted timesheet record {}.", id);
+
+    Ok(())
+}
+
+/// Shift a datetime by whole days, keeping its local time of day.
+fn shift_days(
+    datetime: DateTime<Local>,
+    days: chrono::Duration,
+) -> Result<DateTime<Local>, KimaiError> {
+    Local
+        .from_local_datetime(&(datetime.naive_local() + days))
+        .earliest()
+        .ok_or_else(|| KimaiError::Other(format!("{} doesn't exist locally", datetime + days)))
+}
+
+/// Changes to be made to a timesheet record. Fields set to `None` are left
+/// untouched.
+#[derive(Debug, Default)]
+struct TimesheetRecordChanges {
+    project: Option<usize>,
+    activity: Option<usize>,
+    begin: Option<DateTime<Local>>,
+    end: Option<DateTime<Local>>,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+}
+
+impl TimesheetRecordChanges {
+    fn is_empty(&self) -> bool {
+        self.project.is_none()
+            && self.activity.is_none()
+            && self.begin.is_none()
+            && self.end.is_none()
+            && self.description.is_none()
+            && self.tags.is_none()
+    }
+}
+
+/// A timesheet record as presented for editing in `$EDITOR`
+#[derive(Debug, Deserialize, Serialize)]
+struct EditableTimesheetRecord {
+    begin: String,
+    end: Option<String>,
+    project: usize,
+    activity: usize,
+    description: String,
+    tags: Vec<String>,
+}
+
+impl EditableTimesheetRecord {
+    fn from_record(record: &TimesheetRecord) -> Self {
+        EditableTimesheetRecord {
+            begin: record.begin.format(DATETIME_FORMAT).to_string(),
+            end: record.end.map(|e| e.format(DATETIME_FORMAT).to_string()),
+            project: record.project,
+            activity: record.activity,
+            description: record.description.clone().unwrap_or_default(),
+            tags: record.tags.clone(),
+        }
+    }
+
+    /// Get the changes made compared to the original record.
+    fn changes(&self, original: &Self) -> Result<TimesheetRecordChanges, KimaiError> {
+        fn changed<T: PartialEq + Clone>(new: &T, old: &T) -> Option<T> {
+            match new != old {
+                true => Some(new.clone()),
+                false => None,
+            }
+        }
+        Ok(TimesheetRecordChanges {
+            project: changed(&self.project, &original.project),
+            activity: changed(&self.activity, &original.activity),
+            begin: match changed(&self.begin, &original.begin) {
+                Some(b) => Some(str_to_datetime(&b)?),
+                None => None,
+            },
+            end: match changed(&self.end, &original.end) {
+                Some(Some(e)) => Some(str_to_datetime(&e)?),
+                Some(None) => {
+                    return Err(KimaiError::Other(
+                        "The end of a record can't be removed, restart it instead!".to_string(),
+                    ))
+                }
+                None => None,
+            },
+            description: changed(&self.description, &original.description),
+            tags: changed(&self.tags, &original.tags),
+        })
+    }
+}
+
+/// Open a timesheet record as TOML in `$EDITOR` and return the changes made
+/// to it. Returns `None` if the editor exits with an error.
+fn edit_timesheet_record(
+    record: &TimesheetRecord,
+) -> Result<Option<TimesheetRecordChanges>, KimaiError> {
+    let original = EditableTimesheetRecord::from_record(record);
+    let path = create_temp_file(
+        &format!("kimai-record-{}-{}.toml", record.id, std::process::id()),
+        &toml::to_string(&original)?,
+    )?;
+
+    let edited = match edit_file(&path)? {
+        Some(e) => e,
+        None => return Ok(None),
+    };
+    let edited = toml::from_str::<EditableTimesheetRecord>(&edited)?;
+    Ok(Some(edited.changes(&original)?))
+}
+
+/// Create a new file with the given name in the temporary directory. Fails
+/// if the file, or a symlink by that name, exists already.
+fn create_temp_file(name: &str, contents: &str) -> Result<PathBuf, KimaiError> {
+    let path = std::env::temp_dir().join(name);
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    file.write_all(contents.as_bytes())?;
+    Ok(path)
+}
+
+/// Open a file in `$EDITOR`, remove it afterwards and return what it was
+/// edited to. Returns `None` if the editor exits with an error.
+fn edit_file(path: &Path) -> Result<Option<String>, KimaiError> {
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+    let mut editor_args = editor.split_whitespace();
+    let status = Command::new(editor_args.next().unwrap_or("vi"))
+        .args(editor_args)
+        .arg(path)
+        .status();
+    let edited = fs::read_to_string(path);
+    fs::remove_file(path)?;
+    if !status?.success() {
+        return Ok(None);
+    }
+    Ok(Some(edited?))
+}
+
+/// Set the personal note of a timesheet record. Without a note, the current
+/// one is opened in `$EDITOR`.
+pub fn print_edit_personal_note(id: usize, note: Option<String>) -> Result<(), KimaiError> {
+    let note = match note {
+        Some(n) => n,
+        None => {
+            let notes: BTreeMap<usize, PersonalNote> = read_state(NOTES_FILE)?;
+            let path = create_temp_file(
+                &format!("kimai-note-{}-{}.txt", id, std::process::id()),
+                notes.get(&id).map_or("", |n| n.note.as_str()),
+            )?;
+            match edit_file(&path)? {
+                Some(n) => n,
+                None => {
+                    println!("Editor exited with an error, not changing the note.");
+                    return Ok(());
+                }
+            }
+        }
+    };
+    set_personal_note(id, &note)?;
+    match note.trim().is_empty() {
+        true => println!("Removed the personal note of record {}.", id),
+        false => println!("Set the personal note of record {}.", id),
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn print_change_timesheet_record(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+    project: Option<usize>,
+    activity: Option<usize>,
+    begin: Option<String>,
+    end: Option<String>,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+    shift: Option<String>,
+    move_to_date: Option<NaiveDate>,
+    duration: Option<String>,
+    allow_future: bool,
+    edit: bool,
+    skip_overlap_check: bool,
+    no_prompt: bool,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let record = client.get_timesheet_record(id).await?;
+
+    let mut changes = if edit {
+        match edit_timesheet_record(&record)? {
+            Some(c) => c,
+            None => {
+                println!("Editing cancelled.");
+                return Ok(());
+            }
+        }
+    } else if let Some(s) = shift {
+        let shift = parse_relative_duration(&s)?;
+        TimesheetRecordChanges {
+            begin: Some(record.begin + shift),
+            end: record.end.map(|e| e + shift),
+            ..Default::default()
+        }
+    } else if let Some(date) = move_to_date {
+        let days = date - record.begin.date().naive_local();
+        TimesheetRecordChanges {
+            begin: Some(shift_days(record.begin, days)?),
+            end: match record.end {
+                Some(e) => Some(shift_days(e, days)?),
+                None => None,
+            },
+            ..Default::default()
+        }
+    } else if let Some(d) = duration {
+        let end = record.begin + parse_clock_duration(&d)?;
+        if end > Local::now() && !allow_future {
+            return Err(KimaiError::Other(format!(
+                "The end {} would be in the future, use --allow-future to set it anyway!",
+                end.format(DATETIME_FORMAT)
+            )));
+        }
+        TimesheetRecordChanges {
+            end: Some(end),
+            ..Default::default()
+        }
+    } else {
+        TimesheetRecordChanges::default()
+    };
+    changes.project = project.or(changes.project);
+    changes.activity = activity.or(changes.activity);
+    changes.begin = get_datetime_option(begin)?.or(changes.begin);
+    changes.end = get_datetime_option(end)?.or(changes.end);
+    changes.description = description.or(changes.description);
+    changes.tags = tags.or(changes.tags);
+    if !edit && changes.is_empty() {
+        return Err(KimaiError::Other("Nothing to change given!".to_string()));
+    }
+
+    if (changes.begin.is_some() || changes.end.is_some()) && !skip_overlap_check {
+        let begin = changes.begin.unwrap_or(record.begin);
+        let end = changes.end.or(record.end);
+        let overlapping = client
+            .get_overlapping_timesheet_records(begin, end, Some(id))
+            .await?;
+        if !overlapping.is_empty() {
+            println!("The changed record would overlap with:");
+            print_timesheets(&overlapping);
+            if !confirm("Change anyway?", no_prompt)? {
+                return Err(KimaiError::Other(
+                    "Changed record overlaps with existing records!".to_string(),
+                ));
+            }
+        }
+    }
+
+    let record = client
+        .patch_timesheet_record(
+            id,
+            changes.project,
+            changes.activity,
+            changes.begin,
+            changes.end,
+            changes.description,
+            changes.tags,
+        )
+        .await?;
+    println!("Changed timesheet record:");
+    record.print_table(&client).await;
+
+    Ok(())
+}
+
+/// Outcome of printing the active timesheet records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActiveTimesheetStatus {
+    Ok,
+    /// No record is active to print the elapsed time for
+    NoneActive,
+    /// More records are active than allowed
+    TooManyActive,
+}
+
+/// Print the active timesheet records, or with `elapsed` only the seconds
+/// elapsed since the begin of the first one. Warns if more than
+/// `max_active` records, or `max_active_timers` of the config, are active,
+/// for each profile with `all_profiles`. When watching, the status of the
+/// last refresh is returned.
+///
+/// With `refresh_interval` the records are printed again after every interval
+/// of that many seconds, until `max_refresh_count` is reached if given.
+#[allow(clippy::too_many_arguments)]
+pub async fn print_active_timesheet(
+    config_path: Option<String>,
+    profile: Option<String>,
+    elapsed: bool,
+    output: OutputFormat,
+    idle_threshold: Option<String>,
+    all_profiles: bool,
+    refresh_interval: Option<f64>,
+    max_refresh_count: Option<usize>,
+    hide_id: bool,
+    max_active: Option<usize>,
+) -> Result<ActiveTimesheetStatus, KimaiError> {
+    let seconds = match refresh_interval {
+        Some(s) => s,
+        None => {
+            return print_active_timesheet_once(
+                config_path,
+                profile,
+                elapsed,
+                output,
+                idle_threshold,
+                all_profiles,
+                hide_id,
+                max_active,
+            )
+            .await
+        }
+    };
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs_f64(seconds));
+    let mut count = 0;
+    let mut status = ActiveTimesheetStatus::Ok;
+    while max_refresh_count != Some(count) {
+        interval.tick().await;
+        print!("\x1b[2J\x1b[H");
+        status = print_active_timesheet_once(
+            config_path.clone(),
+            profile.clone(),
+            elapsed,
+            output,
+            idle_threshold.clone(),
+            all_profiles,
+            hide_id,
+            max_active,
+        )
+        .await?;
+        count += 1;
+    }
+
+    Ok(status)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn print_active_timesheet_once(
+    config_path: Option<String>,
+    profile: Option<String>,
+    elapsed: bool,
+    output: OutputFormat,
+    idle_threshold: Option<String>,
+    all_profiles: bool,
+    hide_id: bool,
+    max_active: Option<usize>,
+) -> Result<ActiveTimesheetStatus, KimaiError> {
+    if all_profiles {
+        return print_active_timesheet_of_profiles(
+            config_path,
+            profile,
+            output,
+            idle_threshold,
+            hide_id,
+            max_active,
+        )
+        .await;
+    }
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let config = &client.config;
+    let idle_threshold = match idle_threshold {
+        Some(t) => parse_relative_duration(&t)?,
+        None => config.idle_threshold,
+    };
+
+    let max_active = max_active.unwrap_or(config.max_active_timers);
+
+    let records = client.get_active_timesheet().await?;
+    if elapsed {
+        match records.first() {
+            Some(r) => println!("{}", (Local::now() - r.begin).num_seconds()),
+            None => return Ok(ActiveTimesheetStatus::NoneActive),
+        }
+    } else {
+        match output {
+            OutputFormat::Table | OutputFormat::TableNoBorder => print_timesheet_entities(
+                &records,
+                &[],
+                &read_state::<Vec<Estimate>>(ESTIMATES_FILE)?,
+                Some(idle_threshold),
+                hide_id,
+                config.datetime_format.as_deref(),
+            ),
+            OutputFormat::Json => print_json(&records)?,
+            OutputFormat::Ndjson => print_ndjson(&records)?,
+            OutputFormat::Csv
+            | OutputFormat::Tsv
+            | OutputFormat::Ical
+            | OutputFormat::ClockifyCsv
+            | OutputFormat::TogglCsv
+            | OutputFormat::HarvestCsv
+            | OutputFormat::Markdown
+            | OutputFormat::Org => {
+                return Err(KimaiError::Other(
+                    "Only table and JSON output are supported for active records!".to_string(),
+                ))
+            }
+        }
+    }
+    if records.len() > max_active {
+        eprintln!(
+            "Warning: {} timers are running, at most {} are expected!",
+            records.len(),
+            max_active
+        );
+        return Ok(ActiveTimesheetStatus::TooManyActive);
+    }
+
+    Ok(ActiveTimesheetStatus::Ok)
+}
+
+/// Keep printing the elapsed time of a record in place, polling it every
+/// `refresh_interval` seconds, until it is stopped.
+pub async fn print_watch_timesheet_record(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+    refresh_interval: f64,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs_f64(refresh_interval));
+    let mut watched = false;
+    loop {
+        interval.tick().await;
+        let record = client.get_timesheet_record(id).await?;
+        match record.end {
+            Some(end) => {
+                if watched {
+                    println!();
+                }
+                println!(
+                    "Record {} stopped at {}, total: {}",
+                    id,
+                    end.format(TIME_FORMAT),
+                    format_duration(record.duration, DurationFormat::Clock)
+                );
+                return Ok(());
+            }
+            None => {
+                let elapsed = (Local::now() - record.begin).num_seconds();
+                print!(
+                    "\rRecord {} running for {}:{:02}:{:02}",
+                    id,
+                    elapsed / 3600,
+                    elapsed / 60 % 60,
+                    elapsed % 60
+                );
+                io::stdout().flush()?;
+                watched = true;
+            }
+        }
+    }
+}
+
+/// Records of one profile, to keep the order of the profiles in JSON output
+#[derive(Serialize)]
+struct ProfileRecords<'a, T> {
+    profile: &'a str,
+    records: &'a [T],
+}
+
+/// Print the active timesheet records of all profiles in one table, fetched
+/// concurrently.
+async fn print_active_timesheet_of_profiles(
+    config_path: Option<String>,
+    profile: Option<String>,
+    output: OutputFormat,
+    idle_threshold: Option<String>,
+    hide_id: bool,
+    max_active: Option<usize>,
+) -> Result<ActiveTimesheetStatus, KimaiError> {
+    let profiles = load_profile_clients(config_path, profile)?;
+    let idle_threshold = match idle_threshold {
+        Some(t) => parse_relative_duration(&t)?,
+        None => profiles[0].1.config.idle_threshold,
+    };
+    let datetime_format = profiles[0].1.config.datetime_format.clone();
+
+    let records = futures::future::try_join_all(
+        profiles
+            .iter()
+            .map(|(_, client)| client.get_active_timesheet()),
+    )
+    .await?;
+    let over_limit: Vec<(String, usize, usize)> = profiles
+        .iter()
+        .zip(&records)
+        .filter_map(|((name, client), records)| {
+            let limit = max_active.unwrap_or(client.config.max_active_timers);
+            (records.len() > limit).then(|| (name.clone(), records.len(), limit))
+        })
+        .collect();
+    match output {
+        OutputFormat::Table | OutputFormat::TableNoBorder => {
+            let (names, records): (Vec<String>, Vec<TimesheetRecordEntity>) = profiles
+                .into_iter()
+                .zip(records)
+                .flat_map(|((name, _), records)| {
+                    records.into_iter().map(move |r| (name.clone(), r))
+                })
+                .unzip();
+            print_timesheet_entities(
+                &records,
+                &names,
+                &[],
+                Some(idle_threshold),
+                hide_id,
+                datetime_format.as_deref(),
+            );
+        }
+        OutputFormat::Json => print_json(
+            &profiles
+                .iter()
+                .zip(&records)
+                .map(|((name, _), records)| ProfileRecords {
+                    profile: name,
+                    records,
+                })
+                .collect::<Vec<_>>(),
+        )?,
+        OutputFormat::Csv
+        | OutputFormat::Tsv
+        | OutputFormat::Ical
+        | OutputFormat::Ndjson
+        | OutputFormat::ClockifyCsv
+        | OutputFormat::TogglCsv
+        | OutputFormat::HarvestCsv
+        | OutputFormat::Markdown
+        | OutputFormat::Org => {
+            return Err(KimaiError::Other(
+                "Only table and JSON output are supported for active records of all profiles!"
+                    .to_string(),
+            ))
+        }
+    }
+    for (name, count, limit) in &over_limit {
+        eprintln!(
+            "Warning: {} timers are running for profile \"{}\", at most {} are expected!",
+            count, name, limit
+        );
+    }
+
+    Ok(match over_limit.is_empty() {
+        true => ActiveTimesheetStatus::Ok,
+        false => ActiveTimesheetStatus::TooManyActive,
+    })
+}
+
+/// Print the timesheets of all configured profiles in one table, sorted by
+/// begin, with the projects and activities prefixed by their profile.
+#[allow(clippy::too_many_arguments)]
+pub async fn print_timesheet_of_profiles(
+    config_path: Option<String>,
+    profile: Option<String>,
+    period: Option<Period>,
+    from: Option<String>,
+    to: Option<String>,
+    output: OutputFormat,
+    duration_format: DurationFormat,
+    description_max_length: Option<usize>,
+    datetime_format: Option<String>,
+) -> Result<(), KimaiError> {
+    let profiles = load_profile_clients(config_path, profile)?;
+    let (begin, end) = match period {
+        Some(p) => {
+            let (b, e) = p.bounds(Local::today())?;
+            (Some(b), Some(e))
+        }
+        None => (get_datetime_option(from)?, get_datetime_option(to)?),
+    };
+
+    let timesheets = futures::future::try_join_all(profiles.iter().map(|(_, client)| async move {
+        let mut records = client
+            .get_timesheet(TimesheetQuery {
+                begin,
+                end,
+                ..TimesheetQuery::default()
+            })
+            .await?;
+        records
+            .iter_mut()
+            .for_each(|r| r.canonicalize_tags(&client.config.tag_aliases));
+        let (projects, activities) = client.get_projects_and_activities_of(&records).await?;
+        Ok::<_, KimaiError>((records, projects, activities))
+    }))
+    .await?;
+    match output {
+        OutputFormat::Table | OutputFormat::TableNoBorder => {
+            // Number the projects and activities of all profiles through, as
+            // their IDs are only unique within one Kimai instance
+            let mut records: Vec<TimesheetRecord> = Vec::new();
+            let mut project_names = HashMap::new();
+            let mut activity_names = HashMap::new();
+            for ((name, _), (profile_records, projects, activities)) in
+                profiles.iter().zip(&timesheets)
+            {
+                let mut project_ids = HashMap::new();
+                let mut activity_ids = HashMap::new();
+                for record in profile_records {
+                    let mut record = record.clone();
+                    let next_id = project_names.len();
+                    record.project = *project_ids.entry(record.project).or_insert_with(|| {
+                        let project = projects
+                            .iter()
+                            .find(|p| p.id == record.project)
+                            .map_or_else(|| record.project.to_string(), |p| p.name.clone());
+                        project_names.insert(next_id, format!("{}/{}", name, project));
+                        next_id
+                    });
+                    let next_id = activity_names.len();
+                    record.activity = *activity_ids.entry(record.activity).or_insert_with(|| {
+                        let activity = activities
+                            .iter()
+                            .find(|a| a.id == record.activity)
+                            .map_or_else(|| record.activity.to_string(), |a| a.name.clone());
+                        activity_names.insert(next_id, format!("{}/{}", name, activity));
+                        next_id
+                    });
+                    records.push(record);
+                }
+            }
+            records.sort_by_key(|r| r.begin);
+
+            print_timesheet_table(
+                &records,
+                &[
+                    Column::Begin,
+                    Column::End,
+                    Column::Duration,
+                    Column::Project,
+                    Column::Activity,
+                    Column::Description,
+                ],
+                &CellFormat {
+                    duration_format,
+                    datetime_format,
+                    description_max_length: description_max_length
+                        .unwrap_or(profiles[0].1.config.description_max_length),
+                    borderless: output == OutputFormat::TableNoBorder,
+                    project_names,
+                    activity_names,
+                    ..CellFormat::default()
+                },
+                true,
+            );
+        }
+        OutputFormat::Json => print_json(
+            &profiles
+                .iter()
+                .zip(&timesheets)
+                .map(|((name, _), (records, _, _))| ProfileRecords {
+                    profile: name,
+                    records,
+                })
+                .collect::<Vec<_>>(),
+        )?,
+        OutputFormat::Csv
+        | OutputFormat::Tsv
+        | OutputFormat::Ical
+        | OutputFormat::Ndjson
+        | OutputFormat::ClockifyCsv
+        | OutputFormat::TogglCsv
+        | OutputFormat::HarvestCsv
+        | OutputFormat::Markdown
+        | OutputFormat::Org => {
+            return Err(KimaiError::Other(
+                "Only table and JSON output are supported for the timesheets of all profiles!"
+                    .to_string(),
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn print_recent_timesheet(
+    config_path: Option<String>,
+    profile: Option<String>,
+    user: Option<usize>,
+    mine: bool,
+    begin: Option<String>,
+    hide_id: bool,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+    let user = resolve_user_filter(&client, user, mine).await?;
+
+    let records = client
+        .get_recent_timesheet(user, begin.map(|b| str_to_datetime(&b).unwrap()))
+        .await?;
+    print_timesheet_entities(
+        &records,
+        &[],
+        &[],
+        None,
+        hide_id,
+        client.config.datetime_format.as_deref(),
+    );
+
+    Ok(())
+}
+
+pub async fn print_timesheet_record_status(
+    config_path: Option<String>,
+    profile: Option<String>,
+    id: usize,
+) -> Result<(), KimaiError> {
+    let client = KimaiClient::new(load_config(config_path, profile)?)?;
+
+    let record = client.get_timesheet_record(id).await?;
+    record.print_table(&client).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    #[allow(clippy::assertions_on_constants)]
+    fn basic_test() {
         assert!(true);
     }
+
+    #[tokio::test]
+    async fn test_get_timesheet_customers_query() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/timesheets"))
+            .and(query_param("customers[]", "1"))
+            .and(query_param("customers[]", "2"))
+            .and(query_param("user", "all"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let records = client
+            .get_timesheet(TimesheetQuery {
+                user: Some(TimesheetUser::All),
+                customers: Some(vec![1, 2]),
+                ..TimesheetQuery::default()
+            })
+            .await
+            .unwrap();
+        assert!(records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_timesheet_date_range_query() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/timesheets"))
+            .and(query_param("begin", "2021-06-01T00:00:00"))
+            .and(query_param("end", "2021-06-30T18:30:00"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let records = client
+            .get_timesheet(TimesheetQuery {
+                begin: Some(Local.ymd(2021, 6, 1).and_hms(0, 0, 0)),
+                end: Some(Local.ymd(2021, 6, 30).and_hms(18, 30, 0)),
+                ..TimesheetQuery::default()
+            })
+            .await
+            .unwrap();
+        assert!(records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_timesheet_tags_query() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/timesheets"))
+            .and(query_param("tags", "billable,meeting"))
+            .and(query_param("billable", "1"))
+            .and(query_param("exported", "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let records = client
+            .get_timesheet(TimesheetQuery {
+                exported: Some(false),
+                billable: Some(true),
+                tags: Some(vec!["billable".to_string(), "meeting".to_string()]),
+                ..TimesheetQuery::default()
+            })
+            .await
+            .unwrap();
+        assert!(records.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_timesheet_all_pages() {
+        let record = |id: usize| {
+            serde_json::json!({
+                "id": id,
+                "begin": "2021-06-01T09:00:00+0200",
+                "end": "2021-06-01T10:00:00+0200",
+                "duration": 3600,
+                "description": null,
+                "project": 1,
+                "activity": 1,
+                "user": 1,
+                "tags": [],
+                "exported": false,
+                "rate": 0.0,
+                "hourlyRate": 0.0,
+                "fixedRate": null,
+                "internalRate": 0.0,
+            })
+        };
+        let server = MockServer::start().await;
+        for (page, records) in [("1", vec![record(1), record(2)]), ("2", vec![record(3)])] {
+            Mock::given(method("GET"))
+                .and(path("/api/timesheets"))
+                .and(query_param("page", page))
+                .and(query_param("size", "2"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .insert_header("X-Total-Count", "3")
+                        .set_body_json(serde_json::Value::Array(records)),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let records = client
+            .get_timesheet(TimesheetQuery {
+                size: Some(2),
+                ..TimesheetQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.id).collect::<Vec<usize>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_filtered_timesheet_all_pages() {
+        let record = |id: usize| {
+            serde_json::json!({
+                "id": id,
+                "begin": "2021-06-01T09:00:00+0200",
+                "end": "2021-06-01T10:00:00+0200",
+                "duration": 3600,
+                "description": null,
+                "project": 1,
+                "activity": 1,
+                "user": 1,
+                "tags": [],
+                "exported": false,
+                "rate": 0.0,
+                "hourlyRate": 0.0,
+                "fixedRate": null,
+                "internalRate": 0.0,
+            })
+        };
+        let server = MockServer::start().await;
+        for (page, records) in [("1", vec![record(1), record(2)]), ("2", vec![record(3)])] {
+            Mock::given(method("GET"))
+                .and(path("/api/timesheets"))
+                .and(query_param("page", page))
+                .and(query_param("size", "2"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .insert_header("X-Total-Count", "3")
+                        .set_body_json(serde_json::Value::Array(records)),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let records = get_filtered_timesheet(
+            &client,
+            TimesheetQuery {
+                page: Some(1),
+                size: Some(2),
+                ..TimesheetQuery::default()
+            },
+            &TimesheetOptions {
+                since_record: Some(1),
+                ..TimesheetOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.id).collect::<Vec<usize>>(),
+            vec![2, 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_filtered_timesheet_excluded_all_pages() {
+        let record = |id: usize, project: usize, activity: usize| {
+            serde_json::json!({
+                "id": id,
+                "begin": "2021-06-01T09:00:00+0200",
+                "end": "2021-06-01T10:00:00+0200",
+                "duration": 3600,
+                "description": null,
+                "project": project,
+                "activity": activity,
+                "user": 1,
+                "tags": [],
+                "exported": false,
+                "rate": 0.0,
+                "hourlyRate": 0.0,
+                "fixedRate": null,
+                "internalRate": 0.0,
+            })
+        };
+        let server = MockServer::start().await;
+        for (page, records) in [
+            ("1", vec![record(1, 1, 1), record(2, 2, 1)]),
+            ("2", vec![record(3, 1, 2), record(4, 1, 1)]),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/api/timesheets"))
+                .and(query_param("page", page))
+                .and(query_param("size", "2"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .insert_header("X-Total-Count", "4")
+                        .set_body_json(serde_json::Value::Array(records)),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let records = get_filtered_timesheet(
+            &client,
+            TimesheetQuery {
+                page: Some(1),
+                size: Some(2),
+                ..TimesheetQuery::default()
+            },
+            &TimesheetOptions {
+                exclude_projects: vec![2],
+                exclude_activities: vec![2],
+                ..TimesheetOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.id).collect::<Vec<usize>>(),
+            vec![1, 4]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_missing_timesheet_record() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/timesheets/99"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "code": 404,
+                "message": "Not found"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        assert!(matches!(
+            client.get_timesheet_record(99).await,
+            Err(KimaiError::Api(m)) if m == "404 Not Found: Not found"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_customer_crud() {
+        let customer = serde_json::json!({
+            "id": 3,
+            "name": "Initech",
+            "visible": true,
+            "color": null,
+            "country": "DE",
+            "currency": "EUR",
+            "timezone": "Europe/Berlin"
+        });
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/customers"))
+            .and(body_json(serde_json::json!({
+                "name": "Initech",
+                "visible": true,
+                "color": null,
+                "country": "DE",
+                "currency": "EUR",
+                "timezone": "Europe/Berlin"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&customer))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/customers/3"))
+            .and(body_json(serde_json::json!({ "currency": "USD" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&customer))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/customers/3"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let created = client
+            .create_customer(
+                "Initech".to_string(),
+                true,
+                None,
+                "DE".to_string(),
+                "EUR".to_string(),
+                "Europe/Berlin".to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.id, 3);
+        assert_eq!(created.timezone.as_deref(), Some("Europe/Berlin"));
+        let changes = PatchCustomer {
+            currency: Some("USD".to_string()),
+            ..PatchCustomer::default()
+        };
+        client.update_customer(3, changes).await.unwrap();
+        client.delete_customer(3).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_project_crud() {
+        let project = serde_json::json!({
+            "id": 5,
+            "name": "Intranet",
+            "customer": 1,
+            "parentTitle": "ACME",
+            "visible": true,
+            "color": null,
+            "budget": 0.0,
+            "timeBudget": 36000
+        });
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/projects"))
+            .and(body_json(serde_json::json!({
+                "name": "Intranet",
+                "customer": 1,
+                "visible": true,
+                "color": null,
+                "timeBudget": 36000
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&project))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/projects/5"))
+            .and(body_json(serde_json::json!({ "budget": 1500.0 })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&project))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/projects/5"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let created = client
+            .create_project("Intranet".to_string(), 1, true, None, None, Some(36000))
+            .await
+            .unwrap();
+        assert_eq!(created.time_budget, Some(36000));
+        let changes = PatchProject {
+            budget: Some(1500.0),
+            ..PatchProject::default()
+        };
+        client.update_project(5, changes).await.unwrap();
+        client.delete_project(5).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_global_activity() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/activities"))
+            .and(body_json(serde_json::json!({
+                "name": "Support",
+                "project": null,
+                "visible": true,
+                "color": null
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 4,
+                "name": "Support",
+                "project": null,
+                "parentTitle": null,
+                "visible": true,
+                "color": null
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let activity = client
+            .create_activity("Support".to_string(), None, true, None)
+            .await
+            .unwrap();
+        assert_eq!(activity.project, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_users_and_user() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/users"))
+            .and(query_param("term", "jo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "id": 2, "username": "jdoe", "alias": "John Doe" }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/users/2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 2,
+                "username": "jdoe",
+                "enabled": true,
+                "roles": ["ROLE_TEAMLEAD"],
+                "language": "de",
+                "timezone": "Europe/Berlin",
+                "alias": "John Doe",
+                "title": null,
+                "avatar": null,
+                "teams": [{ "id": 1, "name": "Backend" }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let users = client.get_users(Some("jo".to_string())).await.unwrap();
+        assert_eq!(users.len(), 1);
+        let user = client.get_user(users[0].id).await.unwrap();
+        assert_eq!(user.roles, vec!["ROLE_TEAMLEAD".to_string()]);
+        assert_eq!(user.teams[0].name, "Backend");
+    }
+
+    #[tokio::test]
+    async fn test_get_usernames_without_admin_rights() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/users"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+                "code": 403,
+                "message": "Access denied."
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/users/me"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 2,
+                "username": "jdoe",
+                "enabled": true,
+                "roles": ["ROLE_USER"],
+                "language": "de",
+                "timezone": "Europe/Berlin",
+                "alias": null,
+                "title": null,
+                "avatar": null,
+                "teams": []
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        assert_eq!(
+            client.get_usernames().await,
+            HashMap::from([(2, "jdoe".to_string())])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_team() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/teams/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 1,
+                "name": "Backend",
+                "members": [
+                    { "user": { "id": 2, "username": "jdoe", "alias": null }, "teamlead": true },
+                    { "user": { "id": 3, "username": "mmuster", "alias": "Max" }, "teamlead": false }
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let team = client.get_team(1).await.unwrap();
+        assert_eq!(team.members.len(), 2);
+        assert!(team.members[0].teamlead);
+        assert_eq!(team.members[1].user.username, "mmuster");
+    }
+
+    #[tokio::test]
+    async fn test_create_and_delete_tag() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/tags"))
+            .and(body_json(
+                serde_json::json!({ "name": "billable", "color": "#00ff00" }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 6,
+                "name": "billable",
+                "color": "#00ff00"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/tags/6"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let tag = client
+            .create_tag("billable".to_string(), Some("#00ff00".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(tag.id, 6);
+        assert_eq!(tag.visible, None);
+        client.delete_tag(tag.id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_active_timesheet_json() {
+        let record = serde_json::json!({
+            "id": 7,
+            "begin": "2021-06-01T09:00:00+0200",
+            "end": null,
+            "duration": 0,
+            "description": "Planning",
+            "rate": 0.0,
+            "internalRate": 0.0,
+            "billable": true,
+            "project": {
+                "id": 1,
+                "name": "Website",
+                "visible": true,
+                "color": "#ff8800",
+                "customer": {
+                    "id": 1,
+                    "name": "ACME",
+                    "visible": true,
+                    "color": "#ff0000"
+                }
+            },
+            "activity": {
+                "id": 2,
+                "name": "Development",
+                "visible": true,
+                "color": null,
+                "project": null
+            },
+            "tags": []
+        });
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/timesheets/active"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([record.clone()])),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let records = client.get_active_timesheet().await.unwrap();
+        let json = serde_json::to_value(&records).unwrap();
+        assert_eq!(json[0]["id"], record["id"]);
+        assert_eq!(json[0]["description"], record["description"]);
+        assert_eq!(json[0]["internalRate"], record["internalRate"]);
+        assert_eq!(json[0]["project"]["name"], record["project"]["name"]);
+        assert_eq!(
+            json[0]["project"]["customer"]["name"],
+            record["project"]["customer"]["name"]
+        );
+        assert_eq!(json[0]["activity"]["name"], record["activity"]["name"]);
+    }
+
+    #[tokio::test]
+    async fn test_restart_timesheet_record() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/timesheets/3/restart"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 8,
+                "description": "Planning",
+                "begin": "2021-06-01T09:00:00+0200",
+                "end": null,
+                "duration": 0,
+                "project": 1,
+                "activity": 2,
+                "user": 1,
+                "tags": ["billable"]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/timesheets/4/restart"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "code": 404,
+                "message": "Not found"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let record = client.restart_timesheet_record(3).await.unwrap();
+        assert_eq!(record.id, 8);
+        assert_eq!(record.end, None);
+        assert!(matches!(
+            client.restart_timesheet_record(4).await,
+            Err(KimaiError::Api(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_tag_id() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/tags/find"))
+            .and(query_param("name", "bill"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"id": 4, "name": "billable", "visible": true, "color": null},
+                {"id": 7, "name": "bill", "visible": true, "color": "#ff0000"}
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/tags/find"))
+            .and(query_param("name", "missing"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        assert_eq!(client.get_tag_id("bill").await.unwrap(), 7);
+        assert!(matches!(
+            client.get_tag_id("missing").await,
+            Err(KimaiError::Other(m)) if m == "No tag named missing!"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_timesheet_record() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/timesheets/3"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/timesheets/4"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+                "code": 403,
+                "message": "This timesheet is already exported"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        client.delete_timesheet_record(3).await.unwrap();
+        assert!(matches!(
+            client.delete_timesheet_record(4).await,
+            Err(KimaiError::Api(m)) if m.contains("already exported")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_make_patch_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/timesheets/5"))
+            .and(header("authorization", "Bearer token"))
+            .and(body_json(serde_json::json!({
+                "end": "2021-06-01T12:30:00",
+                "description": "Review",
+                "tags": "a,b"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 5
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let response: serde_json::Value = client
+            .make_patch_request(
+                "api/timesheets/5",
+                Some(PatchTimesheetRecord {
+                    project: None,
+                    activity: None,
+                    begin: None,
+                    end: Some(NaiveDate::from_ymd(2021, 6, 1).and_hms(12, 30, 0)),
+                    description: Some("Review".to_string()),
+                    tags: Some("a,b".to_string()),
+                }),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response["id"], 5);
+    }
+
+    #[tokio::test]
+    async fn test_make_delete_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/timesheets/5"))
+            .and(header("authorization", "Bearer token"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        client
+            .make_delete_request("api/timesheets/5")
+            .await
+            .unwrap();
+        assert!(matches!(
+            client.make_delete_request("api/timesheets/6").await,
+            Err(KimaiError::Api(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_by_ids() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let items = get_by_ids(&[1, 2, 3, 4, 5, 6], 2, |id| {
+            let (running, max_running) = (&running, &max_running);
+            async move {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(id * 10)
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(items, vec![10, 20, 30, 40, 50, 60]);
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_invoice_download_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/invoice"))
+            .and(query_param("template", "2"))
+            .and(query_param("customer", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "url": format!("{}/files/invoice.pdf", server.uri()) }),
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/files/invoice.pdf"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF-1.4".to_vec()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let invoice = client.get_invoice(2, 1, None, None).await.unwrap();
+        assert_eq!(invoice, b"%PDF-1.4");
+    }
+
+    #[tokio::test]
+    async fn test_get_invoice_foreign_download_url() {
+        let server = MockServer::start().await;
+        let storage = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/invoice"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "url": format!("{}/invoice.pdf", storage.uri()) }),
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/invoice.pdf"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF-1.4".to_vec()))
+            .expect(1)
+            .mount(&storage)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        client.get_invoice(2, 1, None, None).await.unwrap();
+        let requests = storage.received_requests().await.unwrap();
+        assert!(!requests[0]
+            .headers
+            .keys()
+            .any(|h| h.as_str().eq_ignore_ascii_case("authorization")));
+    }
+
+    #[tokio::test]
+    async fn test_get_jira_issue_summary() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/2/issue/PROJ-123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "key": "PROJ-123", "fields": { "summary": "Fix login" } }),
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/2/issue/PROJ%2F123%3Fx"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "key": "PROJ-124", "fields": { "summary": "Fix logout" } }),
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // The trailing slash of the host is left out of the issue's URL
+        let config: JiraConfig = toml::from_str(&format!(
+            "host = \"{}/\"\nuser = \"user\"\ntoken = \"token\"",
+            server.uri()
+        ))
+        .unwrap();
+        let summary = jira::get_issue_summary(&config, "PROJ-123").await.unwrap();
+        assert_eq!(summary, "Fix login");
+        let summary = jira::get_issue_summary(&config, "PROJ/123?x")
+            .await
+            .unwrap();
+        assert_eq!(summary, "Fix logout");
+    }
+
+    #[test]
+    fn test_overlapping_pairs() {
+        let record = |id, begin: u32, end: Option<u32>| TimesheetRecord {
+            id,
+            description: None,
+            begin: Local.ymd(2021, 6, 1).and_hms(begin, 0, 0),
+            end: end.map(|e| Local.ymd(2021, 6, 1).and_hms(e, 0, 0)),
+            duration: 0,
+            project: 1,
+            activity: 1,
+            user: 1,
+            tags: Vec::new(),
+            exported: false,
+            rate: 0.0,
+            hourly_rate: 0.0,
+            fixed_rate: None,
+            internal_rate: 0.0,
+            billable: None,
+        };
+        let now = Local.ymd(2021, 6, 1).and_hms(18, 0, 0);
+        let records = vec![
+            record(1, 13, Some(15)),
+            record(2, 9, Some(12)),
+            record(3, 11, Some(14)),
+            record(4, 12, Some(13)),
+            record(5, 17, None),
+        ];
+        assert_eq!(
+            overlapping_pairs(&records, now),
+            vec![(2, 3), (3, 4), (3, 1)]
+        );
+        assert!(overlapping_pairs(&records[3..], now).is_empty());
+    }
+
+    #[test]
+    fn test_record_gaps() {
+        let record = |id, day: u32, begin: u32, end: Option<u32>| TimesheetRecord {
+            id,
+            description: None,
+            begin: Local.ymd(2021, 6, day).and_hms(begin, 0, 0),
+            end: end.map(|e| Local.ymd(2021, 6, day).and_hms(e, 0, 0)),
+            duration: 0,
+            project: 1,
+            activity: 1,
+            user: 1,
+            tags: Vec::new(),
+            exported: false,
+            rate: 0.0,
+            hourly_rate: 0.0,
+            fixed_rate: None,
+            internal_rate: 0.0,
+            billable: None,
+        };
+        let now = Local.ymd(2021, 6, 2).and_hms(18, 0, 0);
+        let records = vec![
+            record(1, 1, 13, Some(15)),
+            record(2, 1, 8, Some(12)),
+            record(3, 1, 11, Some(12)),
+            record(4, 2, 10, None),
+            record(5, 2, 16, Some(17)),
+        ];
+        let gaps = record_gaps(&records, Some(NaiveTime::from_hms(9, 0, 0)), now);
+        assert_eq!(gaps[&2], 0);
+        assert_eq!(gaps[&3], 0);
+        assert_eq!(gaps[&1], 3600);
+        assert_eq!(gaps[&4], 3600);
+        assert_eq!(gaps[&5], 0);
+        assert_eq!(record_gaps(&records, None, now)[&2], 8 * 3600);
+    }
+
+    #[test]
+    fn test_ical_calendar() {
+        let event = ical::Event {
+            uid: "timesheet-1@localhost".to_string(),
+            begin: Utc.ymd(2021, 6, 1).and_hms(9, 0, 0).with_timezone(&Local),
+            end: Utc.ymd(2021, 6, 1).and_hms(12, 30, 0).with_timezone(&Local),
+            summary: "Website / Meeting".to_string(),
+            description: Some("Planning; budget, scope\nand \\ more".to_string()),
+        };
+        let calendar = ical::calendar(&[event]);
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.contains("\r\nDTSTART:20210601T090000Z\r\n"));
+        assert!(calendar.contains("\r\nDTEND:20210601T123000Z\r\n"));
+        assert!(
+            calendar.contains("\r\nDESCRIPTION:Planning\\; budget\\, scope\\nand \\\\ more\r\n")
+        );
+        assert!(calendar.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+
+        let long = ical::Event {
+            uid: "1".to_string(),
+            begin: Local::now(),
+            end: Local::now(),
+            summary: "ä".repeat(50),
+            description: None,
+        };
+        assert!(ical::calendar(&[long]).split("\r\n").all(|l| l.len() <= 75));
+    }
+
+    #[test]
+    fn test_records_overlap() {
+        let record = TimesheetRecord {
+            id: 1,
+            description: None,
+            begin: Local.ymd(2021, 6, 1).and_hms(9, 0, 0),
+            end: Some(Local.ymd(2021, 6, 1).and_hms(12, 0, 0)),
+            duration: 3 * 3600,
+            project: 1,
+            activity: 1,
+            user: 1,
+            tags: Vec::new(),
+            exported: false,
+            rate: 0.0,
+            hourly_rate: 0.0,
+            fixed_rate: None,
+            internal_rate: 0.0,
+            billable: None,
+        };
+        let new_record = |begin: (u32, u32), end: (u32, u32)| NewTimesheetRecord {
+            project: 1,
+            activity: 1,
+            begin: NaiveDate::from_ymd(2021, 6, 1).and_hms(begin.0, begin.1, 0),
+            end: Some(NaiveDate::from_ymd(2021, 6, 1).and_hms(end.0, end.1, 0)),
+            description: None,
+            tags: None,
+            meta_fields: HashMap::new(),
+        };
+        assert!(records_overlap(&record, &new_record((11, 0), (13, 0))));
+        assert!(records_overlap(&record, &new_record((8, 0), (9, 30))));
+        assert!(records_overlap(&record, &new_record((10, 0), (11, 0))));
+        assert!(!records_overlap(&record, &new_record((12, 0), (13, 0))));
+        assert!(!records_overlap(&record, &new_record((7, 0), (9, 0))));
+    }
+
+    #[test]
+    fn test_editable_timesheet_record_changes() {
+        let original = EditableTimesheetRecord {
+            begin: "2021-06-01 09:00".to_string(),
+            end: Some("2021-06-01 17:00".to_string()),
+            project: 1,
+            activity: 2,
+            description: "Planning".to_string(),
+            tags: vec!["billable".to_string()],
+        };
+        let edited = toml::from_str::<EditableTimesheetRecord>(
+            &toml::to_string(&original)
+                .unwrap()
+                .replace("17:00", "16:30")
+                .replace("Planning", "Coding"),
+        )
+        .unwrap();
+        let changes = edited.changes(&original).unwrap();
+        assert_eq!(changes.begin, None);
+        assert_eq!(changes.end, Some(Local.ymd(2021, 6, 1).and_hms(16, 30, 0)));
+        assert_eq!(changes.project, None);
+        assert_eq!(changes.activity, None);
+        assert_eq!(changes.description, Some("Coding".to_string()));
+        assert_eq!(changes.tags, None);
+
+        let edited = toml::from_str::<EditableTimesheetRecord>(
+            &toml::to_string(&original)
+                .unwrap()
+                .replace("end = \"2021-06-01 17:00\"\n", ""),
+        )
+        .unwrap();
+        assert!(edited.changes(&original).is_err());
+    }
+
+    #[test]
+    fn test_weekly_totals() {
+        let record = |id, begin: Date<Local>, duration| TimesheetRecord {
+            id,
+            description: None,
+            begin: begin.and_hms(9, 0, 0),
+            end: None,
+            duration,
+            project: 1,
+            activity: 1,
+            user: 1,
+            tags: Vec::new(),
+            exported: false,
+            rate: 0.0,
+            hourly_rate: 0.0,
+            fixed_rate: None,
+            internal_rate: 0.0,
+            billable: None,
+        };
+        // The first days of 2021 still belong to the last ISO week of 2020
+        let records = vec![
+            record(1, Local.ymd(2020, 12, 31), 3600),
+            record(2, Local.ymd(2021, 1, 3), 1800),
+            record(3, Local.ymd(2021, 1, 4), 7200),
+        ];
+        assert_eq!(
+            weekly_totals(&records).into_iter().collect::<Vec<_>>(),
+            vec![((2020, 53), (5400, 2)), ((2021, 1), (7200, 1))]
+        );
+        assert!(weekly_totals(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_project_totals() {
+        let record = |id, project, duration| TimesheetRecord {
+            id,
+            description: None,
+            begin: Local.ymd(2021, 6, 1).and_hms(9, 0, 0),
+            end: None,
+            duration,
+            project,
+            activity: 1,
+            user: 1,
+            tags: Vec::new(),
+            exported: false,
+            rate: 0.0,
+            hourly_rate: 0.0,
+            fixed_rate: None,
+            internal_rate: 0.0,
+            billable: None,
+        };
+        let records = vec![record(1, 1, 3600), record(2, 2, 7200), record(3, 1, 1800)];
+        assert_eq!(project_totals(&records), vec![(2, 1, 7200), (1, 2, 5400)]);
+        assert!(project_totals(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_day_bounds() {
+        let record = |begin: u32, end: Option<u32>| TimesheetRecord {
+            id: 1,
+            description: None,
+            begin: Local.ymd(2021, 6, 1).and_hms(begin, 0, 0),
+            end: end.map(|e| Local.ymd(2021, 6, 1).and_hms(e, 0, 0)),
+            duration: 0,
+            project: 1,
+            activity: 1,
+            user: 1,
+            tags: Vec::new(),
+            exported: false,
+            rate: 0.0,
+            hourly_rate: 0.0,
+            fixed_rate: None,
+            internal_rate: 0.0,
+            billable: None,
+        };
+        let now = Local.ymd(2021, 6, 1).and_hms(18, 0, 0);
+        let day = Local.ymd(2021, 6, 1);
+        assert_eq!(
+            day_bounds(&[record(13, Some(17)), record(9, Some(12))], now),
+            Some((day.and_hms(9, 0, 0), day.and_hms(17, 0, 0)))
+        );
+        assert_eq!(
+            day_bounds(&[record(9, Some(12)), record(13, None)], now),
+            Some((day.and_hms(9, 0, 0), now))
+        );
+        assert_eq!(day_bounds(&[], now), None);
+    }
+
+    #[test]
+    fn test_round_to_increment() {
+        let m = chrono::Duration::minutes;
+        assert_eq!(round_to_increment(m(67), m(15)), m(60));
+        assert_eq!(round_to_increment(m(68), m(15)), m(75));
+        assert_eq!(round_to_increment(m(52), m(15)), m(45));
+        assert_eq!(round_to_increment(m(60), m(15)), m(60));
+        assert_eq!(round_to_increment(m(7), m(15)), m(15));
+        assert_eq!(
+            round_to_increment(chrono::Duration::seconds(1), m(15)),
+            m(15)
+        );
+        assert_eq!(round_to_increment(m(0), m(15)), m(0));
+        assert_eq!(round_to_increment(m(67), m(0)), m(67));
+    }
+
+    #[test]
+    fn test_round_up_to_increment() {
+        let m = chrono::Duration::minutes;
+        assert_eq!(round_up_to_increment(m(61), m(15)), m(75));
+        assert_eq!(round_up_to_increment(m(60), m(15)), m(60));
+        assert_eq!(round_up_to_increment(m(1), m(15)), m(15));
+        assert_eq!(round_up_to_increment(m(0), m(15)), m(0));
+        assert_eq!(
+            round_up_to_increment(chrono::Duration::seconds(3601), m(15)),
+            m(75)
+        );
+        assert_eq!(round_up_to_increment(m(67), m(0)), m(67));
+    }
+
+    #[test]
+    fn test_format_money() {
+        assert_eq!(format_money(120.0, Some("EUR")), "€120.00");
+        assert_eq!(format_money(9.5, Some("CHF")), "CHF 9.50");
+        assert_eq!(format_money(1.234, None), "1.23");
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        assert_eq!(levenshtein(&chars("kitten"), &chars("sitting")), 3);
+        assert_eq!(levenshtein(&chars(""), &chars("abc")), 3);
+        assert_eq!(fuzzy_score("acme", "ACME Corp"), 100);
+        assert_eq!(fuzzy_score("amce", "ACME Corp"), 50);
+        assert_eq!(fuzzy_score("acmee", "ACME Corp"), 80);
+        assert_eq!(fuzzy_score("globex", "ACME Corp"), 16);
+    }
+
+    #[test]
+    fn test_invoice_preview() {
+        let record =
+            |activity, duration: i64, hourly_rate: f64, fixed_rate: Option<f64>| TimesheetRecord {
+                id: 1,
+                description: None,
+                begin: Local.ymd(2021, 6, 1).and_hms(9, 0, 0),
+                end: None,
+                duration,
+                project: 1,
+                activity,
+                user: 1,
+                tags: Vec::new(),
+                exported: false,
+                rate: fixed_rate.unwrap_or(duration as f64 / 3600.0 * hourly_rate),
+                hourly_rate,
+                fixed_rate,
+                internal_rate: 0.0,
+                billable: None,
+            };
+        let activities = vec![Activity {
+            id: 1,
+            name: "Development".to_string(),
+            project: None,
+            parent_title: None,
+            visible: true,
+            color: None,
+        }];
+        let records = vec![
+            record(1, 3600, 80.0, None),
+            record(1, 5400, 80.0, None),
+            record(1, 1800, 80.0, Some(100.0)),
+            record(2, 3600, 60.0, None),
+        ];
+        let preview = InvoicePreview::new(&records, &activities, Some("EUR".to_string()));
+        assert_eq!(
+            preview.lines,
+            vec![
+                InvoiceLine {
+                    activity: "Development".to_string(),
+                    hours: 2.5,
+                    rate: 80.0,
+                    fixed_rate: false,
+                    amount: 200.0,
+                },
+                InvoiceLine {
+                    activity: "Development".to_string(),
+                    hours: 0.5,
+                    rate: 100.0,
+                    fixed_rate: true,
+                    amount: 100.0,
+                },
+                InvoiceLine {
+                    activity: "2".to_string(),
+                    hours: 1.0,
+                    rate: 60.0,
+                    fixed_rate: false,
+                    amount: 60.0,
+                },
+            ]
+        );
+        assert_eq!(preview.total, 360.0);
+    }
+
+    #[test]
+    fn test_shift_days() {
+        assert_eq!(
+            shift_days(
+                Local.ymd(2021, 6, 1).and_hms(23, 30, 0),
+                Local.ymd(2021, 5, 30).naive_local() - Local.ymd(2021, 6, 1).naive_local()
+            )
+            .unwrap(),
+            Local.ymd(2021, 5, 30).and_hms(23, 30, 0)
+        );
+    }
+
+    #[test]
+    fn test_truncate_with_ellipsis() {
+        assert_eq!(truncate_with_ellipsis("Fix bug", 10), "Fix bug");
+        assert_eq!(truncate_with_ellipsis("Fix the bug", 8), "Fix the…");
+        assert_eq!(truncate_with_ellipsis("Fix the bug", 8).chars().count(), 8);
+    }
+
+    #[test]
+    fn test_remember_recent_project() {
+        let recent_project = |project| RecentProject {
+            project,
+            activity: 1,
+        };
+        let mut recent: Vec<RecentProject> =
+            (1..=MAX_RECENT_PROJECTS).map(recent_project).collect();
+        remember_recent_project(&mut recent, recent_project(3));
+        assert_eq!(recent.len(), MAX_RECENT_PROJECTS);
+        assert_eq!(recent[0], recent_project(3));
+        assert_eq!(recent.iter().filter(|r| r.project == 3).count(), 1);
+
+        remember_recent_project(&mut recent, recent_project(42));
+        assert_eq!(recent.len(), MAX_RECENT_PROJECTS);
+        assert_eq!(recent[0], recent_project(42));
+        assert!(!recent.contains(&recent_project(MAX_RECENT_PROJECTS)));
+    }
+
+    #[test]
+    fn test_estimate_outcome() {
+        assert_eq!(estimate_outcome(3630, 3600), "Estimate of 1:00 met");
+        assert_eq!(
+            estimate_outcome(4200, 3600),
+            "Estimate of 1:00 exceeded by 0:10"
+        );
+        assert_eq!(
+            estimate_outcome(2700, 3600),
+            "Estimate of 1:00 under-run by 0:15"
+        );
+    }
+
+    #[test]
+    fn test_validate_datetime_format() {
+        assert!(validate_datetime_format("%Y-%m-%d").is_ok());
+        assert!(validate_datetime_format("%d.%m. %H:%M").is_ok());
+        assert!(validate_datetime_format("%Q").is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(9000, DurationFormat::Clock), "2:30");
+        assert_eq!(format_duration(9000, DurationFormat::Decimal), "2.50");
+        assert_eq!(format_duration(36000 + 300, DurationFormat::Clock), "10:05");
+    }
+
+    #[test]
+    fn test_tsv_field() {
+        assert_eq!(
+            tsv_field("Planning\tsprint\r\nnotes"),
+            "Planning sprint  notes"
+        );
+        assert_eq!(tsv_field("Planning"), "Planning");
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        let (begin, end) = Period::ThisWeek.bounds(Local.ymd(2021, 6, 3)).unwrap();
+        assert_eq!(begin, Local.ymd(2021, 5, 31).and_hms(0, 0, 0));
+        assert_eq!(end, Local.ymd(2021, 6, 7).and_hms(0, 0, 0));
+
+        let (begin, end) = Period::ThisMonth.bounds(Local.ymd(2021, 12, 15)).unwrap();
+        assert_eq!(begin, Local.ymd(2021, 12, 1).and_hms(0, 0, 0));
+        assert_eq!(end, Local.ymd(2021, 12, 16).and_hms(0, 0, 0));
+
+        let (begin, end) = Period::LastMonth.bounds(Local.ymd(2021, 1, 15)).unwrap();
+        assert_eq!(begin, Local.ymd(2020, 12, 1).and_hms(0, 0, 0));
+        assert_eq!(end, Local.ymd(2021, 1, 1).and_hms(0, 0, 0));
+
+        let (begin, end) = Period::ThisQuarter.bounds(Local.ymd(2021, 12, 15)).unwrap();
+        assert_eq!(begin, Local.ymd(2021, 10, 1).and_hms(0, 0, 0));
+        assert_eq!(end, Local.ymd(2022, 1, 1).and_hms(0, 0, 0));
+
+        let (begin, end) = Period::LastQuarter.bounds(Local.ymd(2021, 2, 15)).unwrap();
+        assert_eq!(begin, Local.ymd(2020, 10, 1).and_hms(0, 0, 0));
+        assert_eq!(end, Local.ymd(2021, 1, 1).and_hms(0, 0, 0));
+
+        let (begin, end) = Period::LastQuarter.bounds(Local.ymd(2021, 5, 15)).unwrap();
+        assert_eq!(begin, Local.ymd(2021, 1, 1).and_hms(0, 0, 0));
+        assert_eq!(end, Local.ymd(2021, 4, 1).and_hms(0, 0, 0));
+    }
+
+    #[test]
+    fn test_format_delta() {
+        assert_eq!(format_delta(9000), "▲ +2h30");
+        assert_eq!(format_delta(-4500), "▼ -1h15");
+        assert_eq!(format_delta(0), "0h00");
+    }
+
+    #[test]
+    fn test_working_days() {
+        let day = |d| NaiveDate::from_ymd(2021, 6, d);
+        let model = WorkingTimeModel::default();
+        assert_eq!(model.working_days(day(1), day(1)), 0);
+        assert_eq!(model.working_days(day(4), day(7)), 1);
+        assert_eq!(
+            model.working_days(day(1), NaiveDate::from_ymd(2021, 7, 1)),
+            22
+        );
+        assert_eq!(model.target_seconds(22), 22 * 8 * 3600);
+
+        let part_time = WorkingTimeModel {
+            hours_per_day: 6.5,
+            days_per_week: 3,
+            ..WorkingTimeModel::default()
+        };
+        assert_eq!(part_time.working_days(day(1), day(8)), 3);
+        assert_eq!(part_time.target_seconds(3), 3 * 6 * 3600 + 3 * 1800);
+
+        let with_holidays = WorkingTimeModel {
+            holidays: vec![day(2), day(4)],
+            ..WorkingTimeModel::default()
+        };
+        assert_eq!(with_holidays.working_days(day(1), day(8)), 3);
+    }
+
+    #[test]
+    fn test_progress_bar() {
+        assert_eq!(progress_bar(0.5, 4), "██░░");
+        assert_eq!(progress_bar(0.0, 4), "░░░░");
+        assert_eq!(progress_bar(1.5, 4), "████");
+        assert_eq!(progress_bar(-1.0, 4), "░░░░");
+    }
+
+    #[test]
+    fn test_humanize_datetime() {
+        let now = Local.ymd(2021, 6, 10).and_hms(17, 0, 0);
+        let humanize = |d, h, m| humanize_datetime(Local.ymd(2021, 6, d).and_hms(h, m, 0), now);
+        assert_eq!(humanize(10, 16, 59), Some("1 minute ago".to_string()));
+        assert_eq!(humanize(10, 17, 0), Some("just now".to_string()));
+        assert_eq!(
+            humanize(10, 14, 30),
+            Some("2 hours 30 minutes ago".to_string())
+        );
+        assert_eq!(humanize(10, 14, 0), Some("3 hours ago".to_string()));
+        assert_eq!(humanize(9, 14, 30), Some("yesterday at 14:30".to_string()));
+        assert_eq!(humanize(7, 9, 0), Some("last Monday at 09:00".to_string()));
+        assert_eq!(humanize(1, 9, 0), None);
+    }
+
+    #[test]
+    fn test_parse_yaml_timesheet_records() {
+        let records = parse_yaml_timesheet_records(
+            "---\nbegin: 2021-06-01 09:00\nend: \"2021-06-01 10:30\"\nproject: 1\nactivity: 2\n\
+             description: 'Planning: sprint'\ntags: [a, \"b\"]\n---\n# second one\n\
+             begin: 2021-06-02 09:00\nproject: 3\nactivity: 4\ntags:\n  - c\n  - d\n",
+        )
+        .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                ImportTimesheetRecord {
+                    begin: "2021-06-01 09:00".to_string(),
+                    end: Some("2021-06-01 10:30".to_string()),
+                    project: 1,
+                    activity: 2,
+                    description: Some("Planning: sprint".to_string()),
+                    tags: Some("a,b".to_string()),
+                },
+                ImportTimesheetRecord {
+                    begin: "2021-06-02 09:00".to_string(),
+                    project: 3,
+                    activity: 4,
+                    tags: Some("c,d".to_string()),
+                    ..Default::default()
+                },
+            ]
+        );
+        assert!(parse_yaml_timesheet_records("begin: 2021-06-01 09:00\nproject: 1\n").is_err());
+        assert!(parse_yaml_timesheet_records("project: x\n").is_err());
+        assert!(parse_yaml_timesheet_records("billable: true\n").is_err());
+        assert_eq!(
+            parse_yaml_timesheet_records(
+                "begin: 2021-06-01 09:00 # start\nproject: 1\nactivity: 2\ntags: a\n---\n"
+            )
+            .unwrap()[0]
+                .tags,
+            Some("a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_budget_thresholds() {
+        let thresholds = BudgetThresholds::default();
+        assert_eq!(thresholds.exceeded(0.5), None);
+        assert_eq!(
+            thresholds.exceeded(0.8),
+            Some((0.7, colored::Color::Yellow))
+        );
+        assert_eq!(thresholds.exceeded(1.2), Some((0.9, colored::Color::Red)));
+
+        let thresholds = BudgetThresholds::new(0.5, 0.5).unwrap();
+        assert_eq!(thresholds.exceeded(0.6), Some((0.5, colored::Color::Red)));
+        assert!(BudgetThresholds::new(0.9, 0.7).is_err());
+        assert!(BudgetThresholds::new(0.5, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_clockify_row() {
+        let row = clockify::row(&clockify::Entry {
+            description: "Planning".to_string(),
+            email: "me@example.com".to_string(),
+            project: "ACME - Website".to_string(),
+            task: "Development".to_string(),
+            tags: vec!["a".to_string(), "b".to_string()],
+            billable: true,
+            begin: Local.ymd(2021, 6, 1).and_hms(9, 0, 0),
+            end: Local.ymd(2021, 6, 1).and_hms(17, 30, 0),
+        });
+        assert_eq!(
+            row,
+            vec![
+                "Planning",
+                "me@example.com",
+                "ACME - Website",
+                "Development",
+                "a, b",
+                "Yes",
+                "06/01/2021",
+                "09:00:00",
+                "06/01/2021",
+                "17:30:00",
+            ]
+        );
+        assert_eq!(row.len(), clockify::COLUMNS.len());
+    }
+
+    #[test]
+    fn test_toggl_row() {
+        let row = toggl::row(&toggl::Entry {
+            user: "jdoe".to_string(),
+            email: "jdoe@example.com".to_string(),
+            client: "ACME".to_string(),
+            project: "Website".to_string(),
+            task: "Development".to_string(),
+            description: "Planning".to_string(),
+            billable: false,
+            begin: Local.ymd(2021, 6, 1).and_hms(9, 0, 0),
+            end: Local.ymd(2021, 6, 1).and_hms(11, 30, 15),
+            tags: vec!["a".to_string(), "b".to_string()],
+            amount: 0.0,
+        });
+        assert_eq!(
+            row,
+            vec![
+                "jdoe",
+                "jdoe@example.com",
+                "ACME",
+                "Website",
+                "Development",
+                "Planning",
+                "No",
+                "2021-06-01",
+                "09:00:00",
+                "2021-06-01",
+                "11:30:15",
+                "02:30:15",
+                "a, b",
+                "0.00",
+            ]
+        );
+        assert_eq!(row.len(), toggl::COLUMNS.len());
+    }
+
+    #[test]
+    fn test_harvest_row() {
+        let entry = harvest::Entry {
+            date: Local.ymd(2021, 6, 1),
+            client: "ACME".to_string(),
+            project: "Website".to_string(),
+            task: "Development".to_string(),
+            notes: "Planning".to_string(),
+            duration: 9000,
+        };
+        assert_eq!(
+            harvest::row(&entry, harvest::DEFAULT_DATE_FORMAT),
+            vec![
+                "06/01/2021",
+                "ACME",
+                "Website",
+                "Development",
+                "Planning",
+                "2.50"
+            ]
+        );
+        assert_eq!(harvest::row(&entry, "%Y-%m-%d")[0], "2021-06-01");
+    }
+
+    #[test]
+    fn test_template() {
+        let template: Template = toml::from_str(
+            "project = 42\nactivity = 7\n\
+             description = \"TICKET-{ticket_number}: {title} {not a variable}\"\n\
+             tags = [\"{ticket_number}\", \"support\"]",
+        )
+        .unwrap();
+        assert_eq!(template.variables(), vec!["ticket_number", "title"]);
+
+        let values: HashMap<String, String> = vec![
+            ("ticket_number".to_string(), "123".to_string()),
+            ("title".to_string(), "Fix login".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            template.render(&values),
+            Template {
+                project: Some(42),
+                activity: Some(7),
+                description: Some("TICKET-123: Fix login {not a variable}".to_string()),
+                tags: vec!["123".to_string(), "support".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_markdown_table() {
+        let table = markdown::table(
+            &["ID", "Description"],
+            &[
+                vec!["7".to_string(), "Planning | review".to_string()],
+                vec!["12".to_string(), "".to_string()],
+            ],
+            &[true, false],
+        );
+        assert_eq!(
+            table,
+            "|  ID | Description        |\n\
+             | --: | ------------------ |\n\
+             |   7 | Planning \\| review |\n\
+             |  12 |                    |\n"
+        );
+    }
+
+    #[test]
+    fn test_org_table() {
+        let table = org::table(
+            &["ID", "Description"],
+            &[
+                vec!["7".to_string(), "Planning | review".to_string()],
+                vec!["12".to_string(), "".to_string()],
+            ],
+            &[true, false],
+        );
+        assert_eq!(
+            table,
+            "| ID | Description             |\n\
+             |----+-------------------------|\n\
+             |  7 | Planning \\vert{} review |\n\
+             | 12 |                         |\n"
+        );
+    }
+
+    #[test]
+    fn test_column_from_str() {
+        assert_eq!("id".parse::<Column>().unwrap(), Column::Id);
+        assert_eq!("Billable".parse::<Column>().unwrap(), Column::Billable);
+        assert_eq!("EXPORTED".parse::<Column>().unwrap(), Column::Exported);
+        assert!("billed".parse::<Column>().is_err());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let activity = Activity {
+            id: 2,
+            name: "Development".to_string(),
+            project: None,
+            parent_title: None,
+            visible: true,
+            color: Some("#ff8800".to_string()),
+        };
+        let row = activity.csv_row();
+        assert_eq!(row.len(), Activity::COLUMNS.len());
+        assert_eq!(row, vec!["2", "Development", "", "", "true", "#ff8800"]);
+        let template = InvoiceTemplate {
+            id: 1,
+            name: "Default".to_string(),
+        };
+        assert_eq!(template.csv_row().len(), InvoiceTemplate::COLUMNS.len());
+    }
+
+    #[test]
+    fn test_personal_notes_format() {
+        let notes: BTreeMap<usize, PersonalNote> =
+            serde_json::from_str(r#"{"12": {"note": "Call back on Monday"}}"#).unwrap();
+        assert_eq!(
+            notes.get(&12),
+            Some(&PersonalNote {
+                note: "Call back on Monday".to_string()
+            })
+        );
+        assert_eq!(
+            serde_json::to_string(&notes).unwrap(),
+            r#"{"12":{"note":"Call back on Monday"}}"#
+        );
+    }
+
+    #[test]
+    fn test_palette_color() {
+        assert_eq!(palette_color(3), palette_color(3));
+        assert_ne!(palette_color(3), palette_color(4));
+        assert_eq!(palette_color(3), palette_color(3 + PALETTE.len()));
+    }
+
+    #[test]
+    fn test_hex_to_ansi() {
+        assert_eq!(
+            hex_to_ansi("#ff8000"),
+            Some("\x1b[38;2;255;128;0m".to_string())
+        );
+        assert_eq!(hex_to_ansi("ff8000"), None);
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff8000"), Some((255, 128, 0)));
+        assert_eq!(parse_hex_color("#FF8000"), Some((255, 128, 0)));
+        assert_eq!(parse_hex_color("ff8000"), None);
+        assert_eq!(parse_hex_color("#ff80"), None);
+        assert_eq!(parse_hex_color("#gg8000"), None);
+    }
+
+    #[test]
+    fn test_parse_relative_duration() {
+        assert_eq!(
+            parse_relative_duration("1h30m").unwrap(),
+            chrono::Duration::minutes(90)
+        );
+        assert_eq!(
+            parse_relative_duration("90m").unwrap(),
+            chrono::Duration::minutes(90)
+        );
+        assert_eq!(
+            parse_relative_duration("-10m").unwrap(),
+            chrono::Duration::minutes(-10)
+        );
+        assert_eq!(
+            parse_relative_duration("+1d2h").unwrap(),
+            chrono::Duration::hours(26)
+        );
+        assert!(parse_relative_duration("").is_err());
+        assert!(parse_relative_duration("-").is_err());
+        assert!(parse_relative_duration("10").is_err());
+        assert!(parse_relative_duration("1x").is_err());
+        assert!(parse_relative_duration("h").is_err());
+        assert!(parse_relative_duration("999999999999999999d").is_err());
+        assert!(parse_relative_duration("9223372036854775807s1s").is_err());
+        assert!(parse_relative_duration("99999999999999999999h").is_err());
+    }
+
+    #[test]
+    fn test_parse_clock_duration() {
+        assert_eq!(
+            parse_clock_duration("2:30").unwrap(),
+            chrono::Duration::minutes(150)
+        );
+        assert_eq!(
+            parse_clock_duration("00:05").unwrap(),
+            chrono::Duration::minutes(5)
+        );
+        assert!(parse_clock_duration("2").is_err());
+        assert!(parse_clock_duration("2:60").is_err());
+        assert!(parse_clock_duration("2:5").is_err());
+        assert!(parse_clock_duration("-1:00").is_err());
+    }
+
+    #[test]
+    fn test_canonical_tags() {
+        let aliases: BTreeMap<String, String> = [("bill", "billable"), ("Billable", "billable")]
+            .iter()
+            .map(|(a, t)| (a.to_string(), t.to_string()))
+            .collect();
+        let tags: Vec<String> = ["bill", "meeting", "billable", "Billable"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(
+            canonical_tags(&tags, &aliases),
+            vec!["billable".to_string(), "meeting".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_str_to_datetime() {
+        let expected = Utc.ymd(2021, 6, 3).and_hms(7, 30, 15).with_timezone(&Local);
+        assert_eq!(
+            str_to_datetime("2021-06-03 09:30:15 +0200").unwrap(),
+            expected
+        );
+        assert_eq!(
+            str_to_datetime("2021-06-03T09:30:15+02:00").unwrap(),
+            expected
+        );
+        assert_eq!(
+            str_to_datetime("2021-06-03 09:30").unwrap(),
+            Local.ymd(2021, 6, 3).and_hms(9, 30, 0)
+        );
+        let before = Local::now() - chrono::Duration::minutes(90);
+        let ago = str_to_datetime("1h30m").unwrap();
+        assert!(before <= ago && ago <= Local::now() - chrono::Duration::minutes(90));
+        assert!(str_to_datetime("yesterday").is_err());
+        assert!(str_to_datetime("-1h").is_err());
+        assert!(str_to_datetime("+1h").is_err());
+        assert!(str_to_datetime("1d").is_err());
+        assert!(str_to_datetime("30s").is_err());
+        assert!(str_to_datetime("99999999999h").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_offset() {
+        assert_eq!(
+            parse_duration_offset("1h30m"),
+            Some(chrono::Duration::minutes(90))
+        );
+        assert_eq!(
+            parse_duration_offset("1h30"),
+            Some(chrono::Duration::minutes(90))
+        );
+        assert_eq!(
+            parse_duration_offset("2h"),
+            Some(chrono::Duration::hours(2))
+        );
+        assert_eq!(
+            parse_duration_offset("90m"),
+            Some(chrono::Duration::minutes(90))
+        );
+        assert_eq!(parse_duration_offset("-10m"), None);
+        assert_eq!(parse_duration_offset("1d"), None);
+        assert_eq!(parse_duration_offset("h"), None);
+        assert_eq!(parse_duration_offset("10"), None);
+    }
+
+    #[test]
+    fn test_config_profiles() {
+        let config_file = || {
+            toml::from_str::<ConfigFile>(
+                "host = \"https://top.example\"\ntoken = \"top\"\n\n\
+                 [profiles.work]\nhost = \"https://work.example\"\ntoken = \"work\"\n",
+            )
+            .unwrap()
+        };
+        let config =
+            Config::from_config_file_with_profile(config_file(), None, &EnvOverrides::default())
+                .unwrap();
+        assert_eq!(config.profile, DEFAULT_PROFILE);
+        assert_eq!(config.host, "https://top.example");
+
+        let config = Config::from_config_file_with_profile(
+            config_file(),
+            Some("work"),
+            &EnvOverrides::default(),
+        )
+        .unwrap();
+        assert_eq!(config.profile, "work");
+        assert_eq!(config.host, "https://work.example");
+        assert_eq!(config.token, "work");
+
+        let mut with_default = config_file();
+        with_default.default_profile = Some("work".to_string());
+        let config =
+            Config::from_config_file_with_profile(with_default, None, &EnvOverrides::default())
+                .unwrap();
+        assert_eq!(config.profile, "work");
+
+        assert!(matches!(
+            Config::from_config_file_with_profile(
+                config_file(),
+                Some("missing"),
+                &EnvOverrides::default()
+            ),
+            Err(KimaiError::Config(_))
+        ));
+
+        let nested = toml::from_str::<ConfigFile>(
+            "host = \"https://top.example\"\ntoken = \"top\"\n\n\
+             [profiles.work]\nhost = \"https://work.example\"\ntoken = \"work\"\n\n\
+             [profiles.work.profiles.client]\nhost = \"https://client.example\"\n\
+             token = \"client\"\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            Config::from_config_file_with_profile(nested, Some("work"), &EnvOverrides::default()),
+            Err(KimaiError::Config(_))
+        ));
+
+        let path = std::env::temp_dir().join(format!("kimai-config-{}.toml", std::process::id()));
+        fs::write(&path, "host = \"https://top.example\"\n[profiles.work\n").unwrap();
+        let result = Config::from_path(&path, None);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(KimaiError::Toml(_))));
+    }
+
+    #[test]
+    fn test_config_working_hours_per_day() {
+        let load = |hours: &str| {
+            Config::from_config_file_with_profile(
+                toml::from_str::<ConfigFile>(&format!(
+                    "host = \"https://kimai.example\"\ntoken = \"token\"\n\
+                     working_hours_per_day = {}\n",
+                    hours
+                ))
+                .unwrap(),
+                None,
+                &EnvOverrides::default(),
+            )
+        };
+        assert_eq!(load("6.5").unwrap().working_time_model.hours_per_day, 6.5);
+        assert!(matches!(load("-1.0"), Err(KimaiError::Config(_))));
+        assert!(matches!(load("nan"), Err(KimaiError::Config(_))));
+        assert!(matches!(load("25.0"), Err(KimaiError::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_validate_config_profile() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/users/me"))
+            .and(header("Authorization", "Bearer work"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 2,
+                "username": "jdoe",
+                "enabled": true,
+                "roles": ["ROLE_USER"],
+                "language": "de",
+                "timezone": "Europe/Berlin",
+                "alias": null,
+                "title": null,
+                "avatar": null,
+                "teams": []
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let path = std::env::temp_dir().join(format!("kimai-validate-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            format!(
+                "host = \"ftp://top.example\"\ntoken = \"top\"\n\n\
+                 [profiles.work]\nhost = \"{}\"\ntoken = \"work\"\n",
+                server.uri()
+            ),
+        )
+        .unwrap();
+        let validate = |profile, env| {
+            let path = path.clone();
+            async move { validate_config(Ok(path), profile, &env, &mut Table::new()).await }
+        };
+        let work = validate(Some("work"), EnvOverrides::default()).await;
+        let default = validate(None, EnvOverrides::default()).await;
+        let missing = validate(Some("missing"), EnvOverrides::default()).await;
+        // The environment overrides the top-level host
+        let env = validate(
+            None,
+            EnvOverrides {
+                host: Some(server.uri()),
+                token: Some("work".to_string()),
+            },
+        )
+        .await;
+        fs::remove_file(&path).unwrap();
+        assert!(work);
+        assert!(!default);
+        assert!(!missing);
+        assert!(env);
+    }
+
+    #[test]
+    fn test_config_from_env() {
+        let config_file = toml::from_str::<ConfigFile>(
+            "host = \"https://file.example\"\npass_path = \"kimai/token\"\n",
+        )
+        .unwrap();
+        let env = EnvOverrides {
+            host: None,
+            token: Some("env".to_string()),
+        };
+        let config = Config::from_config_file_with_profile(config_file, None, &env).unwrap();
+        assert_eq!(config.host, "https://file.example");
+        assert_eq!(config.token, "env");
+
+        let env = EnvOverrides::from_vars(|name| match name {
+            HOST_ENV_VAR => Some("https://env.example".to_string()),
+            TOKEN_ENV_VAR => Some("env".to_string()),
+            _ => None,
+        });
+        let not_found = || Err(KimaiError::Config("config file not found!".to_string()));
+        let config = Config::from_found_path(not_found(), None, &env).unwrap();
+        assert_eq!(config.host, "https://env.example");
+        assert_eq!(config.token, "env");
+        assert_eq!(config.profile, DEFAULT_PROFILE);
+        assert!(Config::from_found_path(not_found(), None, &EnvOverrides::default()).is_err());
+        assert!(matches!(
+            Config::from_found_path(not_found(), Some("work"), &env),
+            Err(KimaiError::Config(e)) if e == "profile work not found"
+        ));
+        let profiles = Config::profiles_from_found_path(not_found(), None, &env).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].1.host, "https://env.example");
+
+        // Only the selected profile is overridden
+        let config_file = || {
+            toml::from_str::<ConfigFile>(
+                "host = \"https://top.example\"\ntoken = \"top\"\n\n\
+                 [profiles.work]\nhost = \"https://work.example\"\ntoken = \"work\"\n",
+            )
+            .unwrap()
+        };
+        let profiles =
+            Config::profiles_from_config_file(config_file(), Some("work"), &env).unwrap();
+        assert_eq!(profiles[0].1.host, "https://top.example");
+        assert_eq!(profiles[1].1.host, "https://env.example");
+        assert_eq!(profiles[1].1.token, "env");
+        let profiles = Config::profiles_from_config_file(config_file(), None, &env).unwrap();
+        assert_eq!(profiles[0].1.host, "https://env.example");
+        assert_eq!(profiles[1].1.host, "https://work.example");
+        assert!(matches!(
+            Config::profiles_from_config_file(config_file(), Some("missing"), &env),
+            Err(KimaiError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_timesheet_record_table_names() {
+        let record = TimesheetRecord {
+            id: 1,
+            description: Some("Planning".to_string()),
+            begin: Local.ymd(2021, 6, 1).and_hms(9, 0, 0),
+            end: Some(Local.ymd(2021, 6, 1).and_hms(10, 0, 0)),
+            duration: 3600,
+            project: 4,
+            activity: 7,
+            user: 2,
+            tags: Vec::new(),
+            exported: false,
+            rate: 0.0,
+            hourly_rate: 0.0,
+            fixed_rate: None,
+            internal_rate: 0.0,
+            billable: None,
+        };
+        let table = record.table("Website", "Meeting", "jdoe");
+        let value = |row: usize| {
+            table
+                .get_row(row)
+                .unwrap()
+                .get_cell(1)
+                .unwrap()
+                .get_content()
+        };
+        assert_eq!(value(1), "Website");
+        assert_eq!(value(2), "Meeting");
+        assert_eq!(value(3), "jdoe");
+    }
+
+    #[tokio::test]
+    async fn test_timesheet_record_names_fallback() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/projects/4"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "code": 404,
+                "message": "Not found"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/activities/7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 7,
+                "name": "Meeting",
+                "project": null,
+                "parentTitle": null,
+                "visible": true,
+                "color": null
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/users/me"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 2,
+                "username": "jdoe",
+                "enabled": true,
+                "roles": ["ROLE_USER"],
+                "language": "de",
+                "timezone": "Europe/Berlin",
+                "alias": null,
+                "title": null,
+                "avatar": null,
+                "teams": []
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/users/2"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = KimaiClient::new(Config::new(server.uri(), "token".to_string())).unwrap();
+        let record = TimesheetRecord {
+            id: 1,
+            description: None,
+            begin: Local.ymd(2021, 6, 1).and_hms(9, 0, 0),
+            end: None,
+            duration: 0,
+            project: 4,
+            activity: 7,
+            user: 2,
+            tags: Vec::new(),
+            exported: false,
+            rate: 0.0,
+            hourly_rate: 0.0,
+            fixed_rate: None,
+            internal_rate: 0.0,
+            billable: None,
+        };
+        assert_eq!(
+            record.names(&client).await,
+            ("4".to_string(), "Meeting".to_string(), "jdoe".to_string())
+        );
+    }
+
+    #[test]
+    fn test_totals_row() {
+        let record = |id, duration| TimesheetRecord {
+            id,
+            description: None,
+            begin: Local.ymd(2021, 6, 1).and_hms(9, 0, 0),
+            end: None,
+            duration,
+            project: 1,
+            activity: 1,
+            user: 1,
+            tags: Vec::new(),
+            exported: false,
+            rate: 0.0,
+            hourly_rate: 0.0,
+            fixed_rate: None,
+            internal_rate: 0.0,
+            billable: None,
+        };
+        let row = totals_row(
+            &[record(1, 3600), record(2, 1800)],
+            &[Column::Id, Column::Project, Column::Duration],
+            &CellFormat::default(),
+        );
+        assert_eq!(row.get_cell(0).unwrap().get_content(), "Total");
+        assert_eq!(row.get_cell(1).unwrap().get_content(), "");
+        assert_eq!(
+            row.get_cell(2).unwrap().get_content(),
+            format_duration(5400, DurationFormat::default())
+        );
+    }
 }
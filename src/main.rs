@@ -1,6 +1,7 @@
 use chrono::prelude::*;
 use clap::{
-    crate_authors, crate_description, crate_name, crate_version, values_t, App, Arg, SubCommand,
+    crate_authors, crate_description, crate_name, crate_version, values_t, App, AppSettings, Arg,
+    ArgGroup, SubCommand,
 };
 
 macro_rules! arg {
@@ -34,7 +35,8 @@ macro_rules! arg {
     };
 }
 
-fn main() {
+#[tokio::main]
+async fn main() {
     fn usize_validator(s: String) -> Result<(), String> {
         match s.parse::<usize>() {
             Ok(_) => Ok(()),
@@ -43,19 +45,47 @@ fn main() {
     }
 
     fn datetime_validator(s: String) -> Result<(), String> {
-        match NaiveDateTime::parse_from_str(&s, kimai::DATETIME_FORMAT) {
+        match kimai::str_to_datetime(&s) {
             Ok(_) => Ok(()),
-            Err(_) => match NaiveTime::parse_from_str(&s, kimai::TIME_FORMAT) {
-                Ok(_) => Ok(()),
-                Err(_) => Err(format!(
-                    "DateTime must be of format \"{}\" or \"{}\"!",
-                    kimai::DATETIME_FORMAT,
-                    kimai::TIME_FORMAT
-                )),
-            },
+            Err(_) => Err(format!(
+                "DateTime must be of format \"{}\", \"{}\", RFC 3339 or like \"1h30m\" for that long ago!",
+                kimai::DATETIME_FORMAT,
+                kimai::TIME_FORMAT
+            )),
         }
     }
 
+    fn date_validator(s: String) -> Result<(), String> {
+        match NaiveDate::parse_from_str(&s, kimai::DATE_FORMAT) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(format!(
+                "Date must be of format \"{}\"!",
+                kimai::DATE_FORMAT
+            )),
+        }
+    }
+
+    fn datetime_format_validator(s: String) -> Result<(), String> {
+        kimai::validate_datetime_format(&s).map_err(|e| e.to_string())
+    }
+
+    // Arguments of `timesheet change` which move the begin and end together
+    let moving_args = [
+        "edit",
+        "shift",
+        "move_to_date",
+        "reschedule_to_yesterday",
+        "reschedule_to_today",
+    ];
+
+    let periods = [
+        "week_total_only",
+        "this_month",
+        "last_month",
+        "this_quarter",
+        "last_quarter",
+    ];
+
     let config_path_arg = Arg::with_name("config_path")
         .long("config_path")
         .help("Path to a config file")
@@ -74,6 +104,11 @@ fn main() {
         .validator(usize_validator)
         .help("ID of the user on who's behalf to act");
 
+    let mine_arg = Arg::with_name("mine")
+        .long("mine")
+        .help("Only show records of the current user")
+        .conflicts_with("user");
+
     let projects_arg = Arg::with_name("projects")
         .short("p")
         .long("projects")
@@ -85,6 +120,7 @@ fn main() {
     let customers_arg = Arg::with_name("customers")
         .short("c")
         .long("customers")
+        .alias("customer")
         .help("Limit the returned customers")
         .validator(usize_validator)
         .takes_value(true)
@@ -98,6 +134,10 @@ fn main() {
         .takes_value(true)
         .multiple(true);
 
+    let color_arg = Arg::with_name("color")
+        .long("color")
+        .help("Color names by their color in Kimai");
+
     let begin_arg = arg!(
         "begin",
         "b",
@@ -131,20 +171,223 @@ fn main() {
         "Description to be added to a record"
     );
 
+    fn duration_validator(s: String) -> Result<(), String> {
+        match kimai::parse_relative_duration(&s) {
+            Ok(_) => Ok(()),
+            Err(_) => Err("Duration must be of format like \"1h30m\" or \"-10m\"!".to_string()),
+        }
+    }
+
+    fn clock_duration_validator(s: String) -> Result<(), String> {
+        match kimai::parse_clock_duration(&s) {
+            Ok(_) => Ok(()),
+            Err(_) => Err("Duration must be of format \"HH:MM\"!".to_string()),
+        }
+    }
+
+    fn columns_validator(s: String) -> Result<(), String> {
+        s.split(',')
+            .try_for_each(|c| c.trim().parse::<kimai::Column>().map(|_| ()))
+            .map_err(|e| e.to_string())
+    }
+
+    fn positive_validator(s: String) -> Result<(), String> {
+        match s.parse::<usize>() {
+            Ok(n) if n >= 1 => Ok(()),
+            _ => Err("Input must be an integer of at least 1!".to_string()),
+        }
+    }
+
+    fn seconds_validator(s: String) -> Result<(), String> {
+        match s.parse::<f64>() {
+            Ok(n) if n > 0.0 => Ok(()),
+            _ => Err("Input must be a positive number of seconds!".to_string()),
+        }
+    }
+
+    fn fraction_validator(s: String) -> Result<(), String> {
+        match s.parse::<f64>() {
+            Ok(n) if (0.0..=1.0).contains(&n) => Ok(()),
+            _ => Err("Input must be a fraction between 0.0 and 1.0!".to_string()),
+        }
+    }
+
+    fn hours_validator(s: String) -> Result<(), String> {
+        match s.parse::<f64>() {
+            Ok(n) if (0.0..=24.0).contains(&n) => Ok(()),
+            _ => Err("Input must be a number of hours between 0 and 24!".to_string()),
+        }
+    }
+
+    fn budget_validator(s: String) -> Result<(), String> {
+        match s.parse::<f64>() {
+            Ok(n) if n >= 0.0 => Ok(()),
+            _ => Err("Budget must be a non-negative number!".to_string()),
+        }
+    }
+
+    fn period_of(matches: &clap::ArgMatches) -> Option<kimai::Period> {
+        [
+            ("week_total_only", kimai::Period::ThisWeek),
+            ("this_month", kimai::Period::ThisMonth),
+            ("last_month", kimai::Period::LastMonth),
+            ("this_quarter", kimai::Period::ThisQuarter),
+            ("last_quarter", kimai::Period::LastQuarter),
+        ]
+        .iter()
+        .find(|(a, _)| matches.is_present(a))
+        .map(|(_, p)| *p)
+    }
+
+    fn commit_datetime(git_ref: &str) -> String {
+        let output = std::process::Command::new("git")
+            .args(["log", "-1", "--format=%ai", "--end-of-options", git_ref])
+            .output();
+        match output {
+            Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).trim().to_string(),
+            _ => {
+                eprintln!("Error: No git commit found for \"{}\".", git_ref);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let personal_note_arg = Arg::with_name("personal_note")
+        .long("personal-note")
+        .value_name("text")
+        .help("A note on the record which is only kept on this machine")
+        .takes_value(true);
+    let listing_output_arg = Arg::with_name("output")
+        .long("output")
+        .alias("format")
+        .help("Format of the output")
+        .takes_value(true)
+        .possible_values(&["table", "json", "csv"]);
+    let visible_arg = Arg::with_name("visible")
+        .long("visible")
+        .help("Whether it is visible")
+        .takes_value(true)
+        .possible_values(&["true", "false"]);
+    let hex_color_arg = Arg::with_name("hex_color")
+        .long("color")
+        .value_name("hex")
+        .help("Color, like #ff0000")
+        .takes_value(true);
+    let budget_arg = Arg::with_name("budget")
+        .long("budget")
+        .help("Budget in the currency of the customer")
+        .takes_value(true)
+        .validator(budget_validator);
+    let time_budget_arg = Arg::with_name("time_budget")
+        .long("time-budget")
+        .value_name("HH:MM")
+        .help("Time budget")
+        .takes_value(true)
+        .validator(clock_duration_validator);
+    let delete_force_arg = Arg::with_name("force")
+        .short("f")
+        .long("force")
+        .help("Delete without asking");
+    let hide_id_arg = Arg::with_name("hide_id")
+        .long("hide-id")
+        .help("Don't show the ID column");
     let tags_arg = arg!("tags", "t", "tags", "Tags for a timesheet record").multiple(true);
     let id_arg = arg!("id", "ID of a timesheet record", usize_validator);
+    let activity_from_commit_arg = Arg::with_name("activity_from_commit")
+        .long("activity-from-commit")
+        .help("Use the last git commit message as description");
+    let no_prompt_arg = Arg::with_name("no_prompt")
+        .long("no-prompt")
+        .help("Don't ask any questions, answering them with no");
 
     let matches = App::new(crate_name!())
         .version(crate_version!())
         .author(crate_authors!())
         .about(crate_description!())
+        .arg(
+            Arg::with_name("profile")
+                .long("profile")
+                .global(true)
+                .takes_value(true)
+                .value_name("name")
+                .help("Use the given profile of the config file"),
+        )
+        .subcommand(
+            SubCommand::with_name("config")
+                .author(crate_authors!())
+                .version(crate_version!())
+                .about("Inspect the configuration")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("validate")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Check the configuration and the connection to Kimai")
+                        .arg(&config_path_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Show the active profile, host and user")
+                        .arg(&config_path_arg),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("customers")
                 .author(crate_authors!())
                 .version(crate_version!())
                 .about("Get a list of all customers")
                 .arg(&config_path_arg)
-                .arg(&term_arg),
+                .arg(&term_arg)
+                .arg(
+                    Arg::with_name("fuzzy_term")
+                        .long("fuzzy-term")
+                        .takes_value(true)
+                        .help("A search term allowing for typos"),
+                )
+                .arg(&color_arg)
+                .arg(&listing_output_arg)
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Create a new customer")
+                        .arg(&config_path_arg)
+                        .arg(arg!("name", "Name of the customer"))
+                        .arg(&visible_arg)
+                        .arg(&hex_color_arg)
+                        .arg(arg!("country", "c", "country", "Country code, like DE").required(true))
+                        .arg(arg!("currency", "C", "currency", "Currency code, like EUR").required(true))
+                        .arg(
+                            arg!("timezone", "z", "timezone", "Timezone, like Europe/Berlin")
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("update")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Change a given customer")
+                        .arg(&config_path_arg)
+                        .arg(arg!("id", "ID of a customer", usize_validator))
+                        .arg(arg!("name", "n", "name", "Name of the customer"))
+                        .arg(&visible_arg)
+                        .arg(&hex_color_arg)
+                        .arg(arg!("country", "c", "country", "Country code, like DE"))
+                        .arg(arg!("currency", "C", "currency", "Currency code, like EUR"))
+                        .arg(arg!("timezone", "z", "timezone", "Timezone, like Europe/Berlin")),
+                )
+                .subcommand(
+                    SubCommand::with_name("delete")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Delete a given customer")
+                        .arg(&config_path_arg)
+                        .arg(arg!("id", "ID of a customer", usize_validator))
+                        .arg(&delete_force_arg)
+                        .arg(&no_prompt_arg),
+                ),
         )
         .subcommand(
             SubCommand::with_name("projects")
@@ -153,7 +396,55 @@ fn main() {
                 .about("Get a list of all projects")
                 .arg(&config_path_arg)
                 .arg(&term_arg)
-                .arg(&customers_arg),
+                .arg(&customers_arg)
+                .arg(&color_arg)
+                .arg(&listing_output_arg)
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Create a new project")
+                        .arg(&config_path_arg)
+                        .arg(arg!("name", "Name of the project"))
+                        .arg(
+                            arg!("customer", "c", "customer", "ID of the customer", usize_validator)
+                                .required(true),
+                        )
+                        .arg(&visible_arg)
+                        .arg(&hex_color_arg)
+                        .arg(&budget_arg)
+                        .arg(&time_budget_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("update")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Change a given project")
+                        .arg(&config_path_arg)
+                        .arg(arg!("id", "i", "id", "ID of a project", usize_validator).required(true))
+                        .arg(arg!("name", "n", "name", "Name of the project"))
+                        .arg(arg!(
+                            "customer",
+                            "c",
+                            "customer",
+                            "ID of the customer",
+                            usize_validator
+                        ))
+                        .arg(&visible_arg)
+                        .arg(&hex_color_arg)
+                        .arg(&budget_arg)
+                        .arg(&time_budget_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("delete")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Delete a given project")
+                        .arg(&config_path_arg)
+                        .arg(arg!("id", "i", "id", "ID of a project", usize_validator).required(true))
+                        .arg(&delete_force_arg)
+                        .arg(&no_prompt_arg),
+                ),
         )
         .subcommand(
             SubCommand::with_name("activities")
@@ -162,7 +453,141 @@ fn main() {
                 .about("Get a list of all activities")
                 .arg(&config_path_arg)
                 .arg(&term_arg)
-                .arg(&projects_arg),
+                .arg(&projects_arg)
+                .arg(&color_arg)
+                .arg(&listing_output_arg)
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Create a new activity, which is global without a project")
+                        .arg(&config_path_arg)
+                        .arg(arg!("name", "Name of the activity"))
+                        .arg(arg!(
+                            "project",
+                            "p",
+                            "project",
+                            "ID of the project",
+                            usize_validator
+                        ))
+                        .arg(&visible_arg)
+                        .arg(&hex_color_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("update")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Change a given activity")
+                        .arg(&config_path_arg)
+                        .arg(arg!("id", "ID of an activity", usize_validator))
+                        .arg(arg!("name", "n", "name", "Name of the activity"))
+                        .arg(&visible_arg)
+                        .arg(&hex_color_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("delete")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Delete a given activity")
+                        .arg(&config_path_arg)
+                        .arg(arg!("id", "ID of an activity", usize_validator))
+                        .arg(&delete_force_arg)
+                        .arg(&no_prompt_arg),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("tags")
+                .author(crate_authors!())
+                .version(crate_version!())
+                .about("Get a list of all tags")
+                .arg(&config_path_arg)
+                .arg(&term_arg)
+                .arg(
+                    Arg::with_name("canonical")
+                        .long("canonical")
+                        .help("Show the tags after replacing aliases by their canonical tag"),
+                )
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Create a new tag")
+                        .arg(&config_path_arg)
+                        .arg(arg!("name", "n", "name", "Name of the tag").required(true))
+                        .arg(&hex_color_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("delete")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Delete a given tag")
+                        .arg(&config_path_arg)
+                        .arg(arg!("id", "i", "id", "ID of a tag", usize_validator))
+                        .arg(arg!("name", "n", "name", "Name of a tag, needs Kimai 2.0 or later"))
+                        .group(ArgGroup::with_name("tag").args(&["id", "name"]).required(true))
+                        .arg(&delete_force_arg)
+                        .arg(&no_prompt_arg),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("users")
+                .author(crate_authors!())
+                .version(crate_version!())
+                .about("Inspect users")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Get a list of all users")
+                        .arg(&config_path_arg)
+                        .arg(&term_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Show the details of a given user")
+                        .arg(&config_path_arg)
+                        .arg(arg!("id", "ID of a user", usize_validator)),
+                )
+                .subcommand(
+                    SubCommand::with_name("me")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Show the details of the current user")
+                        .arg(&config_path_arg),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("teams")
+                .author(crate_authors!())
+                .version(crate_version!())
+                .about("Inspect teams")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Get a list of all teams")
+                        .arg(&config_path_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Show a given team with its members")
+                        .arg(&config_path_arg)
+                        .arg(arg!("id", "ID of a team", usize_validator)),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("invoice-templates")
+                .author(crate_authors!())
+                .version(crate_version!())
+                .about("Get a list of all invoice templates")
+                .arg(&config_path_arg)
+                .arg(&listing_output_arg),
         )
         .subcommand(
             SubCommand::with_name("timesheet")
@@ -171,10 +596,459 @@ fn main() {
                 .about("Interact with the time sheet.")
                 .arg(&config_path_arg)
                 .arg(&user_arg)
+                .arg(&mine_arg)
                 .arg(&projects_arg)
                 .arg(&customers_arg)
                 .arg(&activities_arg)
                 .arg(&term_arg)
+                .arg(
+                    Arg::with_name("all_profiles")
+                        .long("all-profiles")
+                        .help("Show the records of all configured profiles in one table")
+                        .conflicts_with_all(&[
+                            "user",
+                            "mine",
+                            "projects",
+                            "customers",
+                            "activities",
+                            "term",
+                            "exclude_activities",
+                            "exclude_projects",
+                            "hide_id",
+                            "columns",
+                            "hide_tags",
+                            "hide_description",
+                            "hide_user",
+                            "show_user",
+                            "hours_per_record",
+                            "show_gaps",
+                            "total_only",
+                            "exported",
+                            "billable",
+                            "tag",
+                            "since_last_export",
+                            "project_summary",
+                            "customer_summary",
+                            "since_commit",
+                            "invoice_preview",
+                            "totals",
+                            "no_totals",
+                            "agg_by_week",
+                            "week_day_breakdown",
+                            "agg_by_month",
+                            "show_day_bounds",
+                            "out",
+                            "date_format",
+                            "user_email",
+                            "working_hours",
+                            "working_days",
+                            "since_record",
+                            "page",
+                            "size",
+                            "all",
+                            "full_text_search",
+                            "color_by_project",
+                            "color_by_activity",
+                            "human_duration",
+                            "overlap_check",
+                            "round_up_to",
+                        ]),
+                )
+                .arg(
+                    Arg::with_name("exclude_activities")
+                        .long("exclude-activity")
+                        .help("Leave out records of the given activities")
+                        .validator(usize_validator)
+                        .takes_value(true)
+                        .multiple(true),
+                )
+                .arg(&hide_id_arg)
+                .arg(
+                    Arg::with_name("columns")
+                        .long("columns")
+                        .value_name("columns")
+                        .help("Comma separated columns to show in this order, e.g. \"id,begin,duration\"")
+                        .takes_value(true)
+                        .validator(columns_validator)
+                        .conflicts_with_all(&[
+                            "hide_id",
+                            "hide_tags",
+                            "hide_description",
+                            "hide_user",
+                            "show_user",
+                            "hours_per_record",
+                            "show_gaps",
+                        ]),
+                )
+                .arg(
+                    Arg::with_name("hide_tags")
+                        .long("hide-tags")
+                        .help("Don't show the tags column"),
+                )
+                .arg(
+                    Arg::with_name("hide_description")
+                        .long("hide-description")
+                        .help("Don't show the description column"),
+                )
+                .arg(
+                    Arg::with_name("hide_user")
+                        .long("hide-user")
+                        .help("Don't show the user column"),
+                )
+                .arg(
+                    Arg::with_name("show_user")
+                        .long("show-user")
+                        .help("Show the user column")
+                        .conflicts_with("hide_user"),
+                )
+                .arg(
+                    Arg::with_name("hours_per_record")
+                        .long("hours-per-record")
+                        .help("Show the duration in decimal hours as well"),
+                )
+                .arg(
+                    Arg::with_name("show_gaps")
+                        .long("show-gaps")
+                        .help("Show the time since the previous record ended"),
+                )
+                .arg(
+                    Arg::with_name("total_only")
+                        .long("total-only")
+                        .help("Only print the total duration of all records"),
+                )
+                .arg(
+                    Arg::with_name("week_total_only")
+                        .long("week-total-only")
+                        .help("Only print the total duration of this week's records")
+                        .conflicts_with_all(&periods[1..]),
+                )
+                .arg(
+                    Arg::with_name("this_month")
+                        .long("this-month")
+                        .help("Only show records of the current month up to today")
+                        .conflicts_with_all(&periods[2..]),
+                )
+                .arg(
+                    Arg::with_name("last_month")
+                        .long("last-month")
+                        .help("Only show records of the previous month")
+                        .conflicts_with_all(&periods[3..]),
+                )
+                .arg(
+                    Arg::with_name("this_quarter")
+                        .long("this-quarter")
+                        .help("Only show records of the current quarter")
+                        .conflicts_with_all(&periods[4..]),
+                )
+                .arg(
+                    Arg::with_name("last_quarter")
+                        .long("last-quarter")
+                        .help("Only show records of the previous quarter"),
+                )
+                .arg(
+                    Arg::with_name("exported")
+                        .long("exported")
+                        .help("Only show records with the given export state")
+                        .takes_value(true)
+                        .possible_values(&["true", "false"]),
+                )
+                .arg(
+                    Arg::with_name("billable")
+                        .long("billable")
+                        .help("Only show records with the given billable state")
+                        .takes_value(true)
+                        .possible_values(&["true", "false"]),
+                )
+                .arg(
+                    Arg::with_name("tag")
+                        .long("tag")
+                        .value_name("tag")
+                        .help("Only show records with any of these tags")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("since_last_export")
+                        .long("since-last-export")
+                        .help("Only show records not exported yet, same as --exported false")
+                        .conflicts_with("exported"),
+                )
+                .arg(
+                    Arg::with_name("project_summary")
+                        .long("project-summary")
+                        .help("Print the total duration per project after the records"),
+                )
+                .arg(
+                    Arg::with_name("customer_summary")
+                        .long("customer-summary")
+                        .help("Print the total duration per customer after the records"),
+                )
+                .arg(
+                    Arg::with_name("from")
+                        .long("from")
+                        .help("Only show records beginning at or after this time")
+                        .takes_value(true)
+                        .validator(datetime_validator)
+                        .conflicts_with_all(&periods),
+                )
+                .arg(
+                    Arg::with_name("since_commit")
+                        .long("since-commit")
+                        .value_name("git-ref")
+                        .help("Only show records beginning at or after the time of this git commit")
+                        .takes_value(true)
+                        .conflicts_with("from")
+                        .conflicts_with_all(&periods),
+                )
+                .arg(
+                    Arg::with_name("to")
+                        .long("to")
+                        .help("Only show records beginning before this time")
+                        .takes_value(true)
+                        .validator(datetime_validator)
+                        .conflicts_with_all(&periods),
+                )
+                .arg(
+                    Arg::with_name("invoice_preview")
+                        .long("invoice-preview")
+                        .help("Summarize the amount to invoice for records not exported yet")
+                        .conflicts_with_all(&["exported", "since_last_export"]),
+                )
+                .arg(
+                    Arg::with_name("totals")
+                        .long("totals")
+                        .help("Print a footer row with the totals")
+                        .overrides_with("no_totals"),
+                )
+                .arg(
+                    Arg::with_name("no_totals")
+                        .long("no-totals")
+                        .help("Don't print a footer row with the totals")
+                        .overrides_with("totals"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .alias("format")
+                        .help("Format of the output")
+                        .takes_value(true)
+                        .possible_values(&[
+                            "table",
+                            "table-no-border",
+                            "table-csv-like",
+                            "json",
+                            "csv",
+                            "ical",
+                            "ndjson",
+                            "clockify-csv",
+                            "toggl-csv",
+                            "harvest-csv",
+                            "markdown",
+                            "org",
+                        ]),
+                )
+                .arg(
+                    Arg::with_name("agg_by_week")
+                        .long("agg-by-week")
+                        .help("Print the total duration per week instead of the records"),
+                )
+                .arg(
+                    Arg::with_name("week_day_breakdown")
+                        .long("week-day-breakdown")
+                        .help("Print the total duration per weekday instead of the records")
+                        .conflicts_with("agg_by_week"),
+                )
+                .arg(
+                    Arg::with_name("agg_by_month")
+                        .long("agg-by-month")
+                        .help("Print the total duration per month instead of the records")
+                        .conflicts_with_all(&["agg_by_week", "week_day_breakdown"]),
+                )
+                .arg(
+                    Arg::with_name("show_day_bounds")
+                        .long("show-day-bounds")
+                        .help("Print when the first record began and the last one ended"),
+                )
+                .arg(
+                    Arg::with_name("out")
+                        .long("out")
+                        .short("o")
+                        .help("File to save the iCalendar output to, e.g. \"timesheet.ics\"")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("date_format")
+                        .long("date-format")
+                        .value_name("strftime")
+                        .help("Format of the date in the Harvest output, \"%m/%d/%Y\" by default")
+                        .takes_value(true)
+                        .validator(datetime_format_validator),
+                )
+                .arg(
+                    Arg::with_name("user_email")
+                        .long("user-email")
+                        .value_name("email")
+                        .help("Email address for the Email column of the Clockify and Toggl output")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("working_hours")
+                        .long("working-hours")
+                        .value_name("n")
+                        .help("Hours per working day to compare against")
+                        .takes_value(true)
+                        .validator(hours_validator),
+                )
+                .arg(
+                    Arg::with_name("working_days")
+                        .long("working-days")
+                        .value_name("n")
+                        .help("Working days per week to compare against, 5 by default")
+                        .takes_value(true)
+                        .possible_values(&["1", "2", "3", "4", "5", "6", "7"]),
+                )
+                .arg(
+                    Arg::with_name("since_record")
+                        .long("since-record")
+                        .value_name("id")
+                        .help("Only show records with a higher ID than this one")
+                        .takes_value(true)
+                        .validator(usize_validator),
+                )
+                .arg(
+                    Arg::with_name("page")
+                        .long("page")
+                        .help("Page of records to show, 1 by default")
+                        .takes_value(true)
+                        .validator(positive_validator),
+                )
+                .arg(
+                    Arg::with_name("size")
+                        .long("size")
+                        .help("Number of records per page")
+                        .takes_value(true)
+                        .validator(positive_validator),
+                )
+                .arg(
+                    Arg::with_name("all")
+                        .long("all")
+                        .help("Show the records of all pages")
+                        .conflicts_with("page"),
+                )
+                .arg(
+                    Arg::with_name("full_text_search")
+                        .long("full-text-search")
+                        .value_name("term")
+                        .help("Only show records with descriptions containing this term")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("color_by_project")
+                        .long("color-by-project")
+                        .help("Give every project's cells a color of its own"),
+                )
+                .arg(
+                    Arg::with_name("color_by_activity")
+                        .long("color-by-activity")
+                        .help("Give every activity's cells a color of its own"),
+                )
+                .arg(
+                    Arg::with_name("human_duration")
+                        .long("human-duration")
+                        .help("Show the begin of recent records like \"3 hours ago\""),
+                )
+                .arg(
+                    Arg::with_name("overlap_check")
+                        .long("overlap-check")
+                        .help("Warn about records overlapping each other"),
+                )
+                .arg(
+                    Arg::with_name("duration_format")
+                        .long("duration-format")
+                        .help("Format of displayed durations")
+                        .takes_value(true)
+                        .possible_values(&["clock", "decimal"]),
+                )
+                .arg(
+                    Arg::with_name("description_max_length")
+                        .long("description-max-length")
+                        .value_name("n")
+                        .help("Cut off descriptions after this many characters, 0 to show them fully")
+                        .takes_value(true)
+                        .validator(usize_validator),
+                )
+                .arg(
+                    Arg::with_name("round_up_to")
+                        .long("round-up-to")
+                        .value_name("minutes")
+                        .help("Display durations rounded up to a multiple of this many minutes")
+                        .takes_value(true)
+                        .validator(usize_validator),
+                )
+                .arg(
+                    Arg::with_name("datetime_format")
+                        .long("datetime-format")
+                        .help("strftime format of displayed begin and end times")
+                        .takes_value(true)
+                        .validator(datetime_format_validator),
+                )
+                .arg(
+                    Arg::with_name("exclude_projects")
+                        .long("exclude-project")
+                        .help("Leave out records of the given projects")
+                        .validator(usize_validator)
+                        .takes_value(true)
+                        .multiple(true),
+                )
+                .subcommand(
+                    SubCommand::with_name("invoice")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Generate an invoice from the time sheet")
+                        .arg(&config_path_arg)
+                        .arg(
+                            arg!(
+                                "template",
+                                "t",
+                                "template",
+                                "ID of an invoice template",
+                                usize_validator
+                            )
+                            .required(true),
+                        )
+                        .arg(
+                            arg!(
+                                "customer",
+                                "c",
+                                "customer",
+                                "ID of the customer to invoice",
+                                usize_validator
+                            )
+                            .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("from")
+                                .long("from")
+                                .help("Only invoice records beginning at or after this time")
+                                .takes_value(true)
+                                .validator(datetime_validator),
+                        )
+                        .arg(
+                            Arg::with_name("to")
+                                .long("to")
+                                .help("Only invoice records beginning before this time")
+                                .takes_value(true)
+                                .validator(datetime_validator),
+                        )
+                        .arg(
+                            Arg::with_name("out")
+                                .long("out")
+                                .short("o")
+                                .help("File to save the invoice to, \"invoice.pdf\" by default")
+                                .takes_value(true),
+                        ),
+                )
                 .subcommand(
                     SubCommand::with_name("recent")
                         .author(crate_authors!())
@@ -182,20 +1056,93 @@ fn main() {
                         .about("View only recent timesheet records")
                         .arg(&config_path_arg)
                         .arg(&begin_arg)
-                        .arg(&user_arg),
+                        .arg(&user_arg)
+                        .arg(&mine_arg)
+                        .arg(&hide_id_arg),
                 )
                 .subcommand(
                     SubCommand::with_name("active")
                         .author(crate_authors!())
                         .version(crate_version!())
                         .about("View only currently active timesheet records")
-                        .arg(&config_path_arg),
+                        .arg(&config_path_arg)
+                        .arg(
+                            Arg::with_name("elapsed").long("elapsed").help(
+                                "Only print the seconds elapsed, exit with 1 if none is active",
+                            ),
+                        )
+                        .arg(
+                            Arg::with_name("output")
+                                .long("output")
+                                .alias("format")
+                                .help("Format of the output")
+                                .takes_value(true)
+                                .possible_values(&["table", "json", "ndjson"])
+                                .conflicts_with("elapsed"),
+                        )
+                        .arg(
+                            Arg::with_name("idle_threshold")
+                                .long("idle-threshold")
+                                .help("Warn about records running for longer, \"2h\" by default")
+                                .takes_value(true)
+                                .validator(duration_validator),
+                        )
+                        .arg(
+                            Arg::with_name("all_profiles")
+                                .long("all-profiles")
+                                .help("Show the active records of all configured profiles")
+                                .conflicts_with("elapsed"),
+                        )
+                        .arg(
+                            Arg::with_name("watch")
+                                .long("watch")
+                                .short("w")
+                                .help("Keep refreshing the active records"),
+                        )
+                        .arg(
+                            Arg::with_name("interval_refresh")
+                                .long("interval-refresh")
+                                .value_name("seconds")
+                                .help("Seconds between refreshes when watching, 1 by default")
+                                .takes_value(true)
+                                .requires("watching")
+                                .validator(seconds_validator),
+                        )
+                        .arg(
+                            Arg::with_name("watch_active_id")
+                                .long("watch-active-id")
+                                .value_name("id")
+                                .help("Keep showing the elapsed time of this record until it's stopped")
+                                .takes_value(true)
+                                .validator(usize_validator)
+                                .conflicts_with_all(&["elapsed", "all_profiles"]),
+                        )
+                        .group(ArgGroup::with_name("watching").args(&["watch", "watch_active_id"]))
+                        .arg(
+                            Arg::with_name("max_refresh_count")
+                                .long("max-refresh-count")
+                                .value_name("n")
+                                .help("Stop refreshing with --watch after this many times")
+                                .takes_value(true)
+                                .requires("watch")
+                                .validator(positive_validator),
+                        )
+                        .arg(
+                            Arg::with_name("max_active")
+                                .long("max-active")
+                                .value_name("n")
+                                .help("Exit with 2 if more records are active, 1 by default")
+                                .takes_value(true)
+                                .validator(usize_validator),
+                        )
+                        .arg(&hide_id_arg),
                 )
                 .subcommand(
                     SubCommand::with_name("status")
+                        .alias("show")
                         .author(crate_authors!())
                         .version(crate_version!())
-                        .about("View the status of a givin timesheet record")
+                        .about("View the status of a given timesheet record")
                         .arg(&config_path_arg)
                         .arg(&id_arg),
                 )
@@ -208,10 +1155,74 @@ fn main() {
                         .arg(&config_path_arg)
                         .arg(&user_arg)
                         .arg(&begin_arg)
-                        .arg(&project_arg.clone().required(true))
-                        .arg(&activity_arg.clone().required(true))
+                        .arg(project_arg.clone().requires("activity").help(
+                            "ID of a Project, selected from the recently used ones if not given",
+                        ))
+                        .arg(&activity_arg)
                         .arg(&description_arg)
-                        .arg(&tags_arg),
+                        .arg(&activity_from_commit_arg)
+                        .arg(&tags_arg)
+                        .arg(&no_prompt_arg)
+                        .arg(
+                            Arg::with_name("check_running")
+                                .long("check-running")
+                                .help("Ask before beginning the record if a timer is already running"),
+                        )
+                        .arg(
+                            Arg::with_name("force")
+                                .short("f")
+                                .long("force")
+                                .help("Begin the record even if the limit of active ones is reached"),
+                        )
+                        .arg(
+                            Arg::with_name("duration_budget")
+                                .long("duration-budget")
+                                .help("Show the time tracked for the project against its budget"),
+                        )
+                        .arg(
+                            Arg::with_name("warn_at")
+                                .long("warn-at")
+                                .value_name("fraction")
+                                .help("Share of the budget to warn about in yellow, 0.7 by default")
+                                .takes_value(true)
+                                .validator(fraction_validator)
+                                .requires("duration_budget"),
+                        )
+                        .arg(
+                            Arg::with_name("critical_at")
+                                .long("critical-at")
+                                .value_name("fraction")
+                                .help("Share of the budget to warn about in red, 0.9 by default")
+                                .takes_value(true)
+                                .validator(fraction_validator)
+                                .requires("duration_budget"),
+                        )
+                        .arg(
+                            Arg::with_name("estimate")
+                                .long("estimate")
+                                .help("How long the record is expected to take, e.g. \"1h30m\"")
+                                .takes_value(true)
+                                .validator(duration_validator),
+                        )
+                        .arg(
+                            Arg::with_name("remind")
+                                .long("remind")
+                                .help("Show a desktop notification after this long, e.g. \"2h\"")
+                                .takes_value(true)
+                                .validator(duration_validator),
+                        )
+                        .arg(
+                            Arg::with_name("git_remote")
+                                .long("git-remote")
+                                .help("Record the origin URL of the current git repository as a custom field"),
+                        )
+                        .arg(
+                            Arg::with_name("start_of_day")
+                                .long("start-of-day")
+                                .help("Begin at today's start of day as set in the config")
+                                .conflicts_with("begin"),
+                        )
+                        .arg(&personal_note_arg),
                 )
                 .subcommand(
                     SubCommand::with_name("end")
@@ -220,7 +1231,14 @@ fn main() {
                         .version(crate_version!())
                         .about("End a given timesheet record")
                         .arg(&config_path_arg)
-                        .arg(&id_arg),
+                        .arg(&id_arg)
+                        .arg(
+                            Arg::with_name("at")
+                                .long("at")
+                                .help("End the record at this time instead of now")
+                                .takes_value(true)
+                                .validator(datetime_validator),
+                        ),
                 )
                 .subcommand(
                     SubCommand::with_name("log")
@@ -228,12 +1246,103 @@ fn main() {
                         .version(crate_version!())
                         .about("Log a new timesheet record")
                         .arg(&config_path_arg)
-                        .arg(&begin_arg.required(true))
+                        .arg(begin_arg.clone().required_unless_one(&["csv", "stdin"]))
                         .arg(&end_arg)
-                        .arg(&project_arg.clone().required(true))
-                        .arg(&activity_arg.clone().required(true))
+                        .arg(
+                            project_arg
+                                .clone()
+                                .required_unless_one(&[
+                                    "csv",
+                                    "stdin",
+                                    "reuse_last",
+                                    "template_file",
+                                ]),
+                        )
+                        .arg(
+                            activity_arg
+                                .clone()
+                                .required_unless_one(&[
+                                    "csv",
+                                    "stdin",
+                                    "reuse_last",
+                                    "template_file",
+                                ]),
+                        )
+                        .arg(
+                            Arg::with_name("reuse_last")
+                                .long("reuse-last")
+                                .help("Reuse project, activity, description and tags of the last record")
+                                .conflicts_with_all(&["csv", "stdin"]),
+                        )
+                        .arg(
+                            Arg::with_name("template_file")
+                                .long("template-file")
+                                .value_name("path")
+                                .help("Take the record from a TOML template, asking for its {variables}")
+                                .takes_value(true)
+                                .conflicts_with_all(&["csv", "stdin", "reuse_last"]),
+                        )
+                        .arg(
+                            Arg::with_name("from_jira")
+                                .long("from-jira")
+                                .value_name("ticket-id")
+                                .help("Use the summary of this Jira issue as description")
+                                .takes_value(true)
+                                .conflicts_with_all(&[
+                                    "csv",
+                                    "stdin",
+                                    "description",
+                                    "activity_from_commit",
+                                ]),
+                        )
                         .arg(&description_arg)
-                        .arg(&tags_arg),
+                        .arg(&activity_from_commit_arg)
+                        .arg(&tags_arg)
+                        .arg(personal_note_arg.clone().conflicts_with_all(&["csv", "stdin"]))
+                        .arg(
+                            Arg::with_name("force")
+                                .short("f")
+                                .long("force")
+                                .help("Log the record even if it overlaps with existing records"),
+                        )
+                        .arg(&no_prompt_arg)
+                        .arg(
+                            Arg::with_name("billing_increment")
+                                .long("billing-increment")
+                                .help("Round the duration to a multiple of this many minutes")
+                                .takes_value(true)
+                                .validator(usize_validator),
+                        )
+                        .arg(
+                            Arg::with_name("csv")
+                                .long("csv")
+                                .help("Log all records from a CSV file")
+                                .takes_value(true)
+                                .conflicts_with_all(&[
+                                    "begin",
+                                    "end",
+                                    "project",
+                                    "activity",
+                                    "description",
+                                    "activity_from_commit",
+                                    "tags",
+                                ]),
+                        )
+                        .arg(
+                            Arg::with_name("stdin")
+                                .long("stdin")
+                                .help("Log all records from YAML documents, separated by \"---\", read from stdin")
+                                .conflicts_with_all(&[
+                                    "csv",
+                                    "begin",
+                                    "end",
+                                    "project",
+                                    "activity",
+                                    "description",
+                                    "activity_from_commit",
+                                    "tags",
+                                ]),
+                        ),
                 )
                 .subcommand(
                     SubCommand::with_name("change")
@@ -241,130 +1350,705 @@ fn main() {
                         .author(crate_authors!())
                         .version(crate_version!())
                         .about("Change a given timesheet record")
-                        .arg(&config_path_arg),
+                        .arg(&config_path_arg)
+                        .arg(&id_arg)
+                        .arg(
+                            arg!(
+                                "shift",
+                                "s",
+                                "shift",
+                                "Shift begin and end by a duration, e.g. \"-10m\"",
+                                duration_validator
+                            )
+                            .allow_hyphen_values(true),
+                        )
+                        .arg(
+                            Arg::with_name("edit")
+                                .short("e")
+                                .long("edit")
+                                .help("Edit the record in $EDITOR")
+                                .conflicts_with("shift"),
+                        )
+                        .arg(
+                            Arg::with_name("move_to_date")
+                                .long("move-to-date")
+                                .help("Move the record to another date, keeping its times")
+                                .takes_value(true)
+                                .validator(date_validator)
+                                .conflicts_with_all(&["shift", "edit"]),
+                        )
+                        .arg(
+                            Arg::with_name("reschedule_to_yesterday")
+                                .long("reschedule-to-yesterday")
+                                .help("Move the record to yesterday, keeping its times")
+                                .conflicts_with_all(&["shift", "edit", "move_to_date"]),
+                        )
+                        .arg(
+                            Arg::with_name("reschedule_to_today")
+                                .long("reschedule-to-today")
+                                .help("Move the record to today, keeping its times")
+                                .conflicts_with_all(&[
+                                    "shift",
+                                    "edit",
+                                    "move_to_date",
+                                    "reschedule_to_yesterday",
+                                ]),
+                        )
+                        .arg(
+                            Arg::with_name("duration")
+                                .long("duration")
+                                .value_name("HH:MM")
+                                .help("Set the end to the begin plus this duration")
+                                .takes_value(true)
+                                .validator(clock_duration_validator)
+                                .conflicts_with_all(&[
+                                    "shift",
+                                    "edit",
+                                    "move_to_date",
+                                    "reschedule_to_yesterday",
+                                    "reschedule_to_today",
+                                ]),
+                        )
+                        .arg(
+                            Arg::with_name("allow_future")
+                                .long("allow-future")
+                                .help("Allow --duration to set an end in the future")
+                                .requires("duration"),
+                        )
+                        .arg(
+                            Arg::with_name("skip_overlap_check")
+                                .long("skip-overlap-check")
+                                .help("Don't check for overlaps with other records"),
+                        )
+                        .arg(project_arg.clone().conflicts_with("edit"))
+                        .arg(activity_arg.clone().conflicts_with("edit"))
+                        .arg(begin_arg.clone().conflicts_with_all(&moving_args))
+                        .arg(
+                            Arg::with_name("end")
+                                .long("end")
+                                .help("An end time")
+                                .takes_value(true)
+                                .validator(datetime_validator)
+                                .conflicts_with_all(&moving_args)
+                                .conflicts_with("duration"),
+                        )
+                        .arg(description_arg.clone().conflicts_with("edit"))
+                        .arg(tags_arg.clone().conflicts_with("edit"))
+                        .arg(&no_prompt_arg),
                 )
                 .subcommand(
                     SubCommand::with_name("restart")
                         .author(crate_authors!())
                         .version(crate_version!())
                         .about("Restart a given timesheet record")
-                        .arg(&config_path_arg),
+                        .arg(&config_path_arg)
+                        .arg(&id_arg),
                 )
                 .subcommand(
                     SubCommand::with_name("delete")
                         .author(crate_authors!())
                         .version(crate_version!())
-                        .about("Delete the given timesheet records")
-                        .arg(&config_path_arg),
+                        .about("Delete a given timesheet record")
+                        .arg(&config_path_arg)
+                        .arg(&id_arg)
+                        .arg(
+                            Arg::with_name("force")
+                                .short("f")
+                                .long("force")
+                                .help("Delete the record without asking"),
+                        )
+                        .arg(&no_prompt_arg),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("notes")
+                .author(crate_authors!())
+                .version(crate_version!())
+                .about("Manage personal notes on timesheet records")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("edit")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Set the personal note of a timesheet record, in $EDITOR if no note is given")
+                        .arg(&id_arg)
+                        .arg(
+                            Arg::with_name("note")
+                                .help("The new note, an empty one removes the note")
+                                .takes_value(true),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("report")
+                .author(crate_authors!())
+                .version(crate_version!())
+                .about("Summarize the time sheet")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("monthly")
+                        .author(crate_authors!())
+                        .version(crate_version!())
+                        .about("Show the hours of this month per project")
+                        .arg(&config_path_arg)
+                        .arg(
+                            Arg::with_name("compare_previous")
+                                .long("compare-previous")
+                                .help("Compare with the hours of the previous month"),
+                        ),
                 ),
         )
         .get_matches();
 
+    let profile = matches.value_of("profile").map(|p| p.to_string());
+
+    if let Some(matches) = matches.subcommand_matches("config") {
+        if let Some(matches) = matches.subcommand_matches("validate") {
+            if !kimai::print_validate_config(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+            )
+            .await
+            .unwrap()
+            {
+                std::process::exit(1);
+            }
+        }
+        if let Some(matches) = matches.subcommand_matches("show") {
+            kimai::print_show_config(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+            )
+            .await
+            .unwrap();
+        }
+    }
+
     if let Some(matches) = matches.subcommand_matches("customers") {
-        kimai::print_customers(
-            matches.value_of("config_path").map(|p| p.to_string()),
-            matches.value_of("term").map(|t| t.to_string()),
-        )
-        .unwrap();
+        if let Some(matches) = matches.subcommand_matches("create") {
+            kimai::print_create_customer(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("name").unwrap().to_string(),
+                matches.value_of("visible") != Some("false"),
+                matches.value_of("hex_color").map(|c| c.to_string()),
+                matches.value_of("country").unwrap().to_string(),
+                matches.value_of("currency").unwrap().to_string(),
+                matches.value_of("timezone").unwrap().to_string(),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("update") {
+            kimai::print_update_customer(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").unwrap().parse().unwrap(),
+                matches.value_of("name").map(|n| n.to_string()),
+                matches.value_of("visible").map(|v| v == "true"),
+                matches.value_of("hex_color").map(|c| c.to_string()),
+                matches.value_of("country").map(|c| c.to_string()),
+                matches.value_of("currency").map(|c| c.to_string()),
+                matches.value_of("timezone").map(|t| t.to_string()),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("delete") {
+            kimai::print_delete_customer(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").unwrap().parse().unwrap(),
+                matches.is_present("force"),
+                matches.is_present("no_prompt"),
+            )
+            .await
+            .unwrap();
+        } else {
+            kimai::print_customers(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("term").map(|t| t.to_string()),
+                matches.value_of("fuzzy_term").map(|t| t.to_string()),
+                matches.is_present("color"),
+                matches
+                    .value_of("output")
+                    .map(|o| o.parse().unwrap())
+                    .unwrap_or_default(),
+            )
+            .await
+            .unwrap();
+        }
     }
 
     if let Some(matches) = matches.subcommand_matches("projects") {
-        kimai::print_projects(
-            matches.value_of("config_path").map(|p| p.to_string()),
-            match matches.is_present("customers") {
-                true => Some(values_t!(matches, "customers", usize).unwrap_or_else(|e| e.exit())),
-                false => None,
-            },
-            matches.value_of("term").map(|t| t.to_string()),
-        )
-        .unwrap();
+        if let Some(matches) = matches.subcommand_matches("create") {
+            kimai::print_create_project(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("name").unwrap().to_string(),
+                matches.value_of("customer").unwrap().parse().unwrap(),
+                matches.value_of("visible") != Some("false"),
+                matches.value_of("hex_color").map(|c| c.to_string()),
+                matches.value_of("budget").map(|b| b.parse().unwrap()),
+                matches
+                    .value_of("time_budget")
+                    .map(|t| kimai::parse_clock_duration(t).unwrap().num_seconds() as usize),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("update") {
+            kimai::print_update_project(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").unwrap().parse().unwrap(),
+                matches.value_of("name").map(|n| n.to_string()),
+                matches.value_of("customer").map(|c| c.parse().unwrap()),
+                matches.value_of("visible").map(|v| v == "true"),
+                matches.value_of("hex_color").map(|c| c.to_string()),
+                matches.value_of("budget").map(|b| b.parse().unwrap()),
+                matches
+                    .value_of("time_budget")
+                    .map(|t| kimai::parse_clock_duration(t).unwrap().num_seconds() as usize),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("delete") {
+            kimai::print_delete_project(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").unwrap().parse().unwrap(),
+                matches.is_present("force"),
+                matches.is_present("no_prompt"),
+            )
+            .await
+            .unwrap();
+        } else {
+            kimai::print_projects(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                match matches.is_present("customers") {
+                    true => {
+                        Some(values_t!(matches, "customers", usize).unwrap_or_else(|e| e.exit()))
+                    }
+                    false => None,
+                },
+                matches.value_of("term").map(|t| t.to_string()),
+                matches.is_present("color"),
+                matches
+                    .value_of("output")
+                    .map(|o| o.parse().unwrap())
+                    .unwrap_or_default(),
+            )
+            .await
+            .unwrap();
+        }
     }
 
     if let Some(matches) = matches.subcommand_matches("activities") {
-        kimai::print_activities(
+        if let Some(matches) = matches.subcommand_matches("create") {
+            kimai::print_create_activity(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("name").unwrap().to_string(),
+                matches.value_of("project").map(|p| p.parse().unwrap()),
+                matches.value_of("visible") != Some("false"),
+                matches.value_of("hex_color").map(|c| c.to_string()),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("update") {
+            kimai::print_update_activity(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").unwrap().parse().unwrap(),
+                matches.value_of("name").map(|n| n.to_string()),
+                matches.value_of("visible").map(|v| v == "true"),
+                matches.value_of("hex_color").map(|c| c.to_string()),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("delete") {
+            kimai::print_delete_activity(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").unwrap().parse().unwrap(),
+                matches.is_present("force"),
+                matches.is_present("no_prompt"),
+            )
+            .await
+            .unwrap();
+        } else {
+            kimai::print_activities(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                match matches.is_present("projects") {
+                    true => {
+                        Some(values_t!(matches, "projects", usize).unwrap_or_else(|e| e.exit()))
+                    }
+                    false => None,
+                },
+                matches.value_of("term").map(|t| t.to_string()),
+                matches.is_present("color"),
+                matches
+                    .value_of("output")
+                    .map(|o| o.parse().unwrap())
+                    .unwrap_or_default(),
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    if let Some(matches) = matches.subcommand_matches("tags") {
+        if let Some(matches) = matches.subcommand_matches("create") {
+            kimai::print_create_tag(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("name").unwrap().to_string(),
+                matches.value_of("hex_color").map(|c| c.to_string()),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("delete") {
+            kimai::print_delete_tag(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").map(|i| i.parse().unwrap()),
+                matches.value_of("name").map(|n| n.to_string()),
+                matches.is_present("force"),
+                matches.is_present("no_prompt"),
+            )
+            .await
+            .unwrap();
+        } else {
+            kimai::print_tags(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("term").map(|t| t.to_string()),
+                matches.is_present("canonical"),
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    if let Some(matches) = matches.subcommand_matches("users") {
+        if let Some(matches) = matches.subcommand_matches("list") {
+            kimai::print_users(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("term").map(|t| t.to_string()),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("show") {
+            kimai::print_user(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                Some(matches.value_of("id").unwrap().parse().unwrap()),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("me") {
+            kimai::print_user(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                None,
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    if let Some(matches) = matches.subcommand_matches("teams") {
+        if let Some(matches) = matches.subcommand_matches("list") {
+            kimai::print_teams(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("show") {
+            kimai::print_team(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").unwrap().parse().unwrap(),
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    if let Some(matches) = matches.subcommand_matches("invoice-templates") {
+        kimai::print_invoice_templates(
             matches.value_of("config_path").map(|p| p.to_string()),
-            match matches.is_present("projects") {
-                true => Some(values_t!(matches, "projects", usize).unwrap_or_else(|e| e.exit())),
-                false => None,
-            },
-            matches.value_of("term").map(|t| t.to_string()),
+            profile.clone(),
+            matches
+                .value_of("output")
+                .map(|o| o.parse().unwrap())
+                .unwrap_or_default(),
         )
+        .await
         .unwrap();
     }
 
     if let Some(matches) = matches.subcommand_matches("timesheet") {
-        if let Some(matches) = matches.subcommand_matches("recent") {
+        if let Some(matches) = matches.subcommand_matches("invoice") {
+            kimai::print_invoice(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("template").unwrap().parse().unwrap(),
+                matches.value_of("customer").unwrap().parse().unwrap(),
+                matches.value_of("from").map(|f| f.to_string()),
+                matches.value_of("to").map(|t| t.to_string()),
+                matches.value_of("out").map(|o| o.to_string()),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("recent") {
             kimai::print_recent_timesheet(
                 matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
                 matches
                     .value_of("user")
                     .map(|u| u.parse::<usize>().unwrap()),
+                matches.is_present("mine"),
                 matches.value_of("begin").map(|p| p.to_string()),
+                matches.is_present("hide_id"),
             )
+            .await
             .unwrap();
         } else if let Some(matches) = matches.subcommand_matches("active") {
-            kimai::print_active_timesheet(matches.value_of("config_path").map(|p| p.to_string()))
+            if let Some(id) = matches.value_of("watch_active_id") {
+                kimai::print_watch_timesheet_record(
+                    matches.value_of("config_path").map(|p| p.to_string()),
+                    profile.clone(),
+                    id.parse().unwrap(),
+                    matches
+                        .value_of("interval_refresh")
+                        .map(|i| i.parse().unwrap())
+                        .unwrap_or(1.0),
+                )
+                .await
                 .unwrap();
+            } else {
+                match kimai::print_active_timesheet(
+                    matches.value_of("config_path").map(|p| p.to_string()),
+                    profile.clone(),
+                    matches.is_present("elapsed"),
+                    matches
+                        .value_of("output")
+                        .map(|o| o.parse().unwrap())
+                        .unwrap_or_default(),
+                    matches.value_of("idle_threshold").map(|t| t.to_string()),
+                    matches.is_present("all_profiles"),
+                    match matches.is_present("watch") {
+                        true => Some(
+                            matches
+                                .value_of("interval_refresh")
+                                .map(|i| i.parse().unwrap())
+                                .unwrap_or(1.0),
+                        ),
+                        false => None,
+                    },
+                    matches
+                        .value_of("max_refresh_count")
+                        .map(|m| m.parse().unwrap()),
+                    matches.is_present("hide_id"),
+                    matches.value_of("max_active").map(|m| m.parse().unwrap()),
+                )
+                .await
+                .unwrap()
+                {
+                    kimai::ActiveTimesheetStatus::Ok => {}
+                    kimai::ActiveTimesheetStatus::NoneActive => std::process::exit(1),
+                    kimai::ActiveTimesheetStatus::TooManyActive => std::process::exit(2),
+                }
+            }
         } else if let Some(matches) = matches.subcommand_matches("status") {
             kimai::print_timesheet_record_status(
                 matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
                 matches.value_of("id").unwrap().parse().unwrap(),
             )
+            .await
             .unwrap();
         } else if let Some(matches) = matches.subcommand_matches("begin") {
             kimai::print_begin_timesheet_record(
                 matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
                 matches
                     .value_of("user")
                     .map(|u| u.parse::<usize>().unwrap()),
-                matches.value_of("project").unwrap().parse().unwrap(),
-                matches.value_of("activity").unwrap().parse().unwrap(),
+                matches.value_of("project").map(|p| p.parse().unwrap()),
+                matches.value_of("activity").map(|a| a.parse().unwrap()),
                 matches.value_of("begin").map(|p| p.to_string()),
-                matches.value_of("description").map(|d| d.to_string()),
+                match matches.value_of("description") {
+                    Some(d) => Some(d.to_string()),
+                    None if matches.is_present("activity_from_commit") => {
+                        kimai::description_from_commit()
+                    }
+                    None => None,
+                },
                 match matches.is_present("tags") {
                     true => Some(values_t!(matches, "tags", String).unwrap_or_else(|e| e.exit())),
                     false => None,
                 },
+                matches.is_present("no_prompt"),
+                matches.is_present("check_running"),
+                matches.is_present("force"),
+                matches.is_present("duration_budget"),
+                matches.value_of("warn_at").map(|w| w.parse().unwrap()),
+                matches.value_of("critical_at").map(|c| c.parse().unwrap()),
+                matches.is_present("start_of_day"),
+                matches.value_of("estimate").map(|e| e.to_string()),
+                matches.is_present("git_remote"),
+                matches.value_of("remind").map(|r| r.to_string()),
+                matches.value_of("personal_note").map(|n| n.to_string()),
             )
+            .await
             .unwrap();
         } else if let Some(matches) = matches.subcommand_matches("end") {
             kimai::print_end_timesheet_record(
                 matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
                 matches.value_of("id").unwrap().parse().unwrap(),
+                matches.value_of("at").map(|a| a.to_string()),
             )
+            .await
             .unwrap();
         } else if let Some(matches) = matches.subcommand_matches("restart") {
-            dbg!(matches);
-            todo!("The restart subcommand still needs to be implemented?");
+            kimai::print_restart_timesheet_record(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").unwrap().parse().unwrap(),
+            )
+            .await
+            .unwrap();
         } else if let Some(matches) = matches.subcommand_matches("change") {
-            dbg!(matches);
-            todo!("The change subcommand still needs to be implemented?");
-        } else if let Some(matches) = matches.subcommand_matches("delete") {
-            dbg!(matches);
-            todo!("The delete subcommand still needs to be implemented?");
-        } else if let Some(matches) = matches.subcommand_matches("log") {
-            kimai::print_log_timesheet_record(
+            kimai::print_change_timesheet_record(
                 matches.value_of("config_path").map(|p| p.to_string()),
-                matches
-                    .value_of("user")
-                    .map(|u| u.parse::<usize>().unwrap()),
-                matches.value_of("project").unwrap().parse().unwrap(),
-                matches.value_of("activity").unwrap().parse().unwrap(),
-                matches.value_of("begin").unwrap().to_string(),
-                matches.value_of("end").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").unwrap().parse().unwrap(),
+                matches.value_of("project").map(|p| p.parse().unwrap()),
+                matches.value_of("activity").map(|a| a.parse().unwrap()),
+                matches.value_of("begin").map(|b| b.to_string()),
+                matches.value_of("end").map(|e| e.to_string()),
                 matches.value_of("description").map(|d| d.to_string()),
                 match matches.is_present("tags") {
                     true => Some(values_t!(matches, "tags", String).unwrap_or_else(|e| e.exit())),
                     false => None,
                 },
+                matches.value_of("shift").map(|s| s.to_string()),
+                if matches.is_present("reschedule_to_yesterday") {
+                    Some(Local::today().naive_local() - chrono::Duration::days(1))
+                } else if matches.is_present("reschedule_to_today") {
+                    Some(Local::today().naive_local())
+                } else {
+                    matches
+                        .value_of("move_to_date")
+                        .map(|d| NaiveDate::parse_from_str(d, kimai::DATE_FORMAT).unwrap())
+                },
+                matches.value_of("duration").map(|d| d.to_string()),
+                matches.is_present("allow_future"),
+                matches.is_present("edit"),
+                matches.is_present("skip_overlap_check"),
+                matches.is_present("no_prompt"),
             )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("delete") {
+            kimai::print_delete_timesheet_record(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.value_of("id").unwrap().parse().unwrap(),
+                matches.is_present("force"),
+                matches.is_present("no_prompt"),
+            )
+            .await
+            .unwrap();
+        } else if let Some(matches) = matches.subcommand_matches("log") {
+            if let Some(csv_path) = matches.value_of("csv") {
+                kimai::print_import_timesheet_records(
+                    matches.value_of("config_path").map(|p| p.to_string()),
+                    profile.clone(),
+                    csv_path.to_string(),
+                )
+                .await
+                .unwrap();
+            } else if matches.is_present("stdin") {
+                kimai::print_import_yaml_timesheet_records(
+                    matches.value_of("config_path").map(|p| p.to_string()),
+                    profile.clone(),
+                )
+                .await
+                .unwrap();
+            } else {
+                kimai::print_log_timesheet_record(
+                    matches.value_of("config_path").map(|p| p.to_string()),
+                    profile.clone(),
+                    matches
+                        .value_of("user")
+                        .map(|u| u.parse::<usize>().unwrap()),
+                    matches.value_of("project").map(|p| p.parse().unwrap()),
+                    matches.value_of("activity").map(|a| a.parse().unwrap()),
+                    matches.value_of("begin").unwrap().to_string(),
+                    matches.value_of("end").map(|p| p.to_string()),
+                    match matches.value_of("description") {
+                        Some(d) => Some(d.to_string()),
+                        None if matches.is_present("activity_from_commit") => {
+                            kimai::description_from_commit()
+                        }
+                        None => None,
+                    },
+                    match matches.is_present("tags") {
+                        true => {
+                            Some(values_t!(matches, "tags", String).unwrap_or_else(|e| e.exit()))
+                        }
+                        false => None,
+                    },
+                    matches.is_present("force"),
+                    matches.is_present("no_prompt"),
+                    matches
+                        .value_of("billing_increment")
+                        .map(|i| i.parse().unwrap()),
+                    matches.is_present("reuse_last"),
+                    matches.value_of("from_jira").map(|t| t.to_string()),
+                    matches.value_of("template_file").map(|t| t.to_string()),
+                    matches.value_of("personal_note").map(|n| n.to_string()),
+                )
+                .await
+                .unwrap();
+            }
+        } else if matches.is_present("all_profiles") {
+            kimai::print_timesheet_of_profiles(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                period_of(matches),
+                matches.value_of("from").map(|f| f.to_string()),
+                matches.value_of("to").map(|t| t.to_string()),
+                matches
+                    .value_of("output")
+                    .map(|o| o.parse().unwrap())
+                    .unwrap_or_default(),
+                matches
+                    .value_of("duration_format")
+                    .map(|f| f.parse().unwrap())
+                    .unwrap_or_default(),
+                matches
+                    .value_of("description_max_length")
+                    .map(|l| l.parse().unwrap()),
+                matches.value_of("datetime_format").map(|f| f.to_string()),
+            )
+            .await
             .unwrap();
         } else {
             kimai::print_timesheet(
                 matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
                 matches
                     .value_of("user")
                     .map(|u| u.parse::<usize>().unwrap()),
+                matches.is_present("mine"),
                 match matches.is_present("customers") {
                     true => {
                         Some(values_t!(matches, "customers", usize).unwrap_or_else(|e| e.exit()))
@@ -383,7 +2067,118 @@ fn main() {
                     }
                     false => None,
                 },
+                period_of(matches),
+                match matches.value_of("since_commit") {
+                    Some(r) => Some(commit_datetime(r)),
+                    None => matches.value_of("from").map(|f| f.to_string()),
+                },
+                matches.value_of("to").map(|t| t.to_string()),
+                match matches.is_present("since_last_export") {
+                    true => Some(false),
+                    false => matches.value_of("exported").map(|e| e == "true"),
+                },
+                kimai::TimesheetOptions {
+                    exclude_activities: values_t!(matches, "exclude_activities", usize)
+                        .unwrap_or_default(),
+                    exclude_projects: values_t!(matches, "exclude_projects", usize)
+                        .unwrap_or_default(),
+                    show_user: matches.is_present("show_user"),
+                    hours_per_record: matches.is_present("hours_per_record"),
+                    show_gaps: matches.is_present("show_gaps"),
+                    hidden_columns: [
+                        ("hide_id", kimai::Column::Id),
+                        ("hide_tags", kimai::Column::Tags),
+                        ("hide_description", kimai::Column::Description),
+                        ("hide_user", kimai::Column::User),
+                    ]
+                    .iter()
+                    .filter(|(a, _)| matches.is_present(a))
+                    .map(|(_, c)| *c)
+                    .collect(),
+                    columns: matches
+                        .value_of("columns")
+                        .map(|c| c.split(',').map(|c| c.trim().parse().unwrap()).collect()),
+                    total_only: matches.is_present("total_only")
+                        || matches.is_present("week_total_only"),
+                    agg_by_week: matches.is_present("agg_by_week"),
+                    week_day_breakdown: matches.is_present("week_day_breakdown"),
+                    agg_by_month: matches.is_present("agg_by_month"),
+                    show_day_bounds: matches.is_present("show_day_bounds"),
+                    overlap_check: matches.is_present("overlap_check"),
+                    out: matches.value_of("out").map(|o| o.to_string()),
+                    working_hours: matches
+                        .value_of("working_hours")
+                        .map(|h| h.parse().unwrap()),
+                    working_days: matches.value_of("working_days").map(|d| d.parse().unwrap()),
+                    since_record: matches.value_of("since_record").map(|i| i.parse().unwrap()),
+                    full_text_search: matches.value_of("full_text_search").map(|t| t.to_string()),
+                    user_email: matches.value_of("user_email").map(|e| e.to_string()),
+                    date_format: matches.value_of("date_format").map(|f| f.to_string()),
+                    color_by_project: matches.is_present("color_by_project"),
+                    color_by_activity: matches.is_present("color_by_activity"),
+                    human_duration: matches.is_present("human_duration"),
+                    project_summary: matches.is_present("project_summary"),
+                    customer_summary: matches.is_present("customer_summary"),
+                    invoice_preview: matches.is_present("invoice_preview"),
+                    totals: if matches.is_present("totals") {
+                        Some(true)
+                    } else if matches.is_present("no_totals") {
+                        Some(false)
+                    } else {
+                        None
+                    },
+                    output: matches
+                        .value_of("output")
+                        .map(|o| o.parse().unwrap())
+                        .unwrap_or_default(),
+                    duration_format: matches
+                        .value_of("duration_format")
+                        .map(|f| f.parse().unwrap())
+                        .unwrap_or_default(),
+                    datetime_format: matches.value_of("datetime_format").map(|f| f.to_string()),
+                    round_up_to: matches.value_of("round_up_to").map(|m| m.parse().unwrap()),
+                    description_max_length: matches
+                        .value_of("description_max_length")
+                        .map(|n| n.parse().unwrap()),
+                    billable: matches.value_of("billable").map(|b| b == "true"),
+                    tags: matches
+                        .values_of("tag")
+                        .map(|t| t.map(|t| t.to_string()).collect()),
+                    page: match matches.is_present("all") {
+                        true => None,
+                        false => Some(
+                            matches
+                                .value_of("page")
+                                .map(|p| p.parse().unwrap())
+                                .unwrap_or(1),
+                        ),
+                    },
+                    page_size: matches.value_of("size").map(|s| s.parse().unwrap()),
+                },
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    if let Some(matches) = matches.subcommand_matches("notes") {
+        if let Some(matches) = matches.subcommand_matches("edit") {
+            kimai::print_edit_personal_note(
+                matches.value_of("id").unwrap().parse().unwrap(),
+                matches.value_of("note").map(|n| n.to_string()),
+            )
+            .unwrap();
+        }
+    }
+
+    if let Some(matches) = matches.subcommand_matches("report") {
+        if let Some(matches) = matches.subcommand_matches("monthly") {
+            kimai::print_monthly_report(
+                matches.value_of("config_path").map(|p| p.to_string()),
+                profile.clone(),
+                matches.is_present("compare_previous"),
             )
+            .await
             .unwrap();
         }
     }
@@ -0,0 +1,48 @@
+//! Rows of Clockify's CSV import for timesheet records
+
+use chrono::prelude::*;
+
+/// Columns of Clockify's CSV import
+pub const COLUMNS: [&str; 10] = [
+    "Description",
+    "Email",
+    "Project",
+    "Task",
+    "Tag",
+    "Billable",
+    "Start Date",
+    "Start Time",
+    "End Date",
+    "End Time",
+];
+
+/// A time entry
+pub struct Entry {
+    pub description: String,
+    pub email: String,
+    pub project: String,
+    pub task: String,
+    pub tags: Vec<String>,
+    pub billable: bool,
+    pub begin: DateTime<Local>,
+    pub end: DateTime<Local>,
+}
+
+/// Get the values of the entry in the order of `COLUMNS`.
+pub fn row(entry: &Entry) -> Vec<String> {
+    vec![
+        entry.description.clone(),
+        entry.email.clone(),
+        entry.project.clone(),
+        entry.task.clone(),
+        entry.tags.join(", "),
+        match entry.billable {
+            true => "Yes".to_string(),
+            false => "No".to_string(),
+        },
+        entry.begin.format("%m/%d/%Y").to_string(),
+        entry.begin.format("%H:%M:%S").to_string(),
+        entry.end.format("%m/%d/%Y").to_string(),
+        entry.end.format("%H:%M:%S").to_string(),
+    ]
+}
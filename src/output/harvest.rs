@@ -0,0 +1,33 @@
+//! Rows of Harvest's CSV import for timesheet records
+
+use chrono::prelude::*;
+
+/// Columns of Harvest's CSV import
+pub const COLUMNS: [&str; 6] = ["Date", "Client", "Project", "Task", "Notes", "Hours"];
+
+/// Format of the date Harvest expects by default
+pub const DEFAULT_DATE_FORMAT: &str = "%m/%d/%Y";
+
+/// A time entry
+pub struct Entry {
+    pub date: Date<Local>,
+    pub client: String,
+    pub project: String,
+    pub task: String,
+    pub notes: String,
+    /// Duration in seconds
+    pub duration: i64,
+}
+
+/// Get the values of the entry in the order of `COLUMNS`, with the date in
+/// the given format and the hours as a decimal.
+pub fn row(entry: &Entry, date_format: &str) -> Vec<String> {
+    vec![
+        entry.date.format(date_format).to_string(),
+        entry.client.clone(),
+        entry.project.clone(),
+        entry.task.clone(),
+        entry.notes.clone(),
+        format!("{:.2}", entry.duration as f64 / 3600.0),
+    ]
+}
@@ -0,0 +1,75 @@
+//! A minimal iCalendar (RFC 5545) serialiser for timesheet records
+
+use chrono::prelude::*;
+
+/// Format of date-times in UTC
+const DATETIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Maximum length of a content line in octets, without the line break
+const LINE_LENGTH: usize = 75;
+
+/// A calendar event
+pub struct Event {
+    pub uid: String,
+    pub begin: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub summary: String,
+    pub description: Option<String>,
+}
+
+/// Escape a text value.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line into lines of at most `LINE_LENGTH` octets, without
+/// splitting characters, and end it with a line break.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+fn format_datetime(datetime: DateTime<Local>) -> String {
+    datetime
+        .with_timezone(&Utc)
+        .format(DATETIME_FORMAT)
+        .to_string()
+}
+
+/// Serialise events as a calendar.
+pub fn calendar(events: &[Event]) -> String {
+    let stamp = format_datetime(Local::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:-//kimai-rs//{}//EN", env!("CARGO_PKG_VERSION")),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", escape(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", format_datetime(event.begin)));
+        lines.push(format!("DTEND:{}", format_datetime(event.end)));
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        if let Some(d) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape(d)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|l| fold(l)).collect()
+}
@@ -0,0 +1,25 @@
+//! Markdown tables, as understood by GitHub and most other renderers
+
+/// Escape the characters which would end a cell or break the table.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Render a table with columns padded to their widest cell, where columns
+/// marked in `right_aligned` are aligned to the right.
+pub fn table(titles: &[&str], rows: &[Vec<String>], right_aligned: &[bool]) -> String {
+    // Separators need at least three dashes
+    super::pipe_table(titles, rows, right_aligned, escape, 3, |widths| {
+        let separators: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(
+                |(i, &width)| match right_aligned.get(i).copied().unwrap_or(false) {
+                    true => format!("{}:", "-".repeat(width - 1)),
+                    false => "-".repeat(width),
+                },
+            )
+            .collect();
+        format!("| {} |\n", separators.join(" | "))
+    })
+}
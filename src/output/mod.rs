@@ -0,0 +1,61 @@
+//! Serialisers for output formats that aren't covered by a crate
+
+pub mod clockify;
+pub mod harvest;
+pub mod ical;
+pub mod markdown;
+pub mod org;
+pub mod toggl;
+
+/// Render a plain text table with its cells between pipes and padded to the
+/// widest cell of their column, at least `min_width`. Columns marked in
+/// `right_aligned` are aligned to the right. `separator` renders the line
+/// below the titles from the widths of the columns.
+fn pipe_table(
+    titles: &[&str],
+    rows: &[Vec<String>],
+    right_aligned: &[bool],
+    escape: fn(&str) -> String,
+    min_width: usize,
+    separator: impl Fn(&[usize]) -> String,
+) -> String {
+    let titles: Vec<String> = titles.iter().map(|t| escape(t)).collect();
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|r| r.iter().map(|c| escape(c)).collect())
+        .collect();
+    let widths: Vec<usize> = titles
+        .iter()
+        .enumerate()
+        .map(|(i, t)| {
+            rows.iter()
+                .filter_map(|r| r.get(i))
+                .chain(std::iter::once(t))
+                .map(|c| c.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(min_width)
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, &width)| {
+                let cell = cells.get(i).map(|c| c.as_str()).unwrap_or("");
+                match right_aligned.get(i).copied().unwrap_or(false) {
+                    true => format!("{:>width$}", cell, width = width),
+                    false => format!("{:<width$}", cell, width = width),
+                }
+            })
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    let mut table = line(&titles);
+    table.push_str(&separator(&widths));
+    for row in &rows {
+        table.push_str(&line(row));
+    }
+    table
+}
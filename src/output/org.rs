@@ -0,0 +1,16 @@
+//! Emacs org-mode tables
+
+/// Replace the characters which would end a cell or break the table.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\vert{}").replace('\n', " ")
+}
+
+/// Render a table with columns padded to their widest cell, where columns
+/// marked in `right_aligned` are aligned to the right, like org-mode does
+/// for numbers.
+pub fn table(titles: &[&str], rows: &[Vec<String>], right_aligned: &[bool]) -> String {
+    super::pipe_table(titles, rows, right_aligned, escape, 0, |widths| {
+        let separators: Vec<String> = widths.iter().map(|&w| "-".repeat(w + 2)).collect();
+        format!("|{}|\n", separators.join("+"))
+    })
+}
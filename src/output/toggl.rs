@@ -0,0 +1,65 @@
+//! Rows of Toggl's detailed CSV export for timesheet records
+
+use chrono::prelude::*;
+
+/// Columns of Toggl's detailed CSV export
+pub const COLUMNS: [&str; 14] = [
+    "User",
+    "Email",
+    "Client",
+    "Project",
+    "Task",
+    "Description",
+    "Billable",
+    "Start date",
+    "Start time",
+    "End date",
+    "End time",
+    "Duration",
+    "Tags",
+    "Amount",
+];
+
+/// A time entry
+pub struct Entry {
+    pub user: String,
+    pub email: String,
+    pub client: String,
+    pub project: String,
+    pub task: String,
+    pub description: String,
+    pub billable: bool,
+    pub begin: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub tags: Vec<String>,
+    pub amount: f64,
+}
+
+/// Get the values of the entry in the order of `COLUMNS`.
+pub fn row(entry: &Entry) -> Vec<String> {
+    let duration = (entry.end - entry.begin).num_seconds();
+    vec![
+        entry.user.clone(),
+        entry.email.clone(),
+        entry.client.clone(),
+        entry.project.clone(),
+        entry.task.clone(),
+        entry.description.clone(),
+        match entry.billable {
+            true => "Yes".to_string(),
+            false => "No".to_string(),
+        },
+        entry.begin.format("%Y-%m-%d").to_string(),
+        entry.begin.format("%H:%M:%S").to_string(),
+        entry.end.format("%Y-%m-%d").to_string(),
+        entry.end.format("%H:%M:%S").to_string(),
+        format!(
+            "{:02}:{:02}:{:02}",
+            duration / 3600,
+            duration / 60 % 60,
+            duration % 60
+        ),
+        entry.tags.join(", "),
+        format!("{:.2}", entry.amount),
+    ]
+}
@@ -0,0 +1,102 @@
+//! Record templates with `{variable}` placeholders in their texts, which are
+//! filled in when logging a record
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Fixed fields of a record, read from a TOML file
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct Template {
+    pub project: Option<usize>,
+    pub activity: Option<usize>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Template {
+    /// Get the names of the variables in the description and tags, in order
+    /// of their first appearance.
+    pub fn variables(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for text in self.description.iter().chain(self.tags.iter()) {
+            for name in variables(text) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    /// Replace the variables in the description and tags by their values.
+    pub fn render(&self, values: &HashMap<String, String>) -> Template {
+        Template {
+            project: self.project,
+            activity: self.activity,
+            description: self.description.as_ref().map(|d| render(d, values)),
+            tags: self.tags.iter().map(|t| render(t, values)).collect(),
+        }
+    }
+}
+
+/// Whether a character may be part of a variable name
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// A part of a text
+enum Part<'a> {
+    Literal(&'a str),
+    Variable(&'a str),
+}
+
+/// Split a text into literal parts and variable names, where a variable is a
+/// name in braces like `{ticket_number}`. Braces around anything else are
+/// kept as they are.
+fn parts(text: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        let after = &rest[open + 1..];
+        match after.find('}') {
+            Some(close) if close > 0 && after[..close].chars().all(is_name_char) => {
+                parts.push(Part::Literal(&rest[..open]));
+                parts.push(Part::Variable(&after[..close]));
+                rest = &after[close + 1..];
+            }
+            _ => {
+                parts.push(Part::Literal(&rest[..=open]));
+                rest = after;
+            }
+        }
+    }
+    parts.push(Part::Literal(rest));
+    parts
+}
+
+/// Get the names of the variables in a text.
+fn variables(text: &str) -> Vec<String> {
+    parts(text)
+        .into_iter()
+        .filter_map(|p| match p {
+            Part::Variable(name) => Some(name.to_string()),
+            Part::Literal(_) => None,
+        })
+        .collect()
+}
+
+/// Replace the variables in a text by their values, leaving ones without a
+/// value as they are.
+fn render(text: &str, values: &HashMap<String, String>) -> String {
+    parts(text)
+        .into_iter()
+        .map(|p| match p {
+            Part::Literal(literal) => literal.to_string(),
+            Part::Variable(name) => values
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| format!("{{{}}}", name)),
+        })
+        .collect()
+}